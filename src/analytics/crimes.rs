@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::{format_month, month_index};
+use crate::models::{Crime, LocationId};
+
+/// Crime counts for a set of street-level crimes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CrimeSummary {
+    /// Number of crimes.
+    pub total: usize,
+    /// Counts by category slug (e.g. "burglary").
+    pub by_category: BTreeMap<String, usize>,
+    /// Counts by month (format: `YYYY-MM`).
+    pub by_month: BTreeMap<String, usize>,
+    /// Counts by approximate street name. Crimes without a location are left
+    /// out.
+    pub by_street: BTreeMap<String, usize>,
+    /// Counts by location type ("Force" or "BTP"). Crimes without a location
+    /// type are left out.
+    pub by_location_type: BTreeMap<String, usize>,
+    /// Number of crimes with no location.
+    pub without_location: usize,
+}
+
+impl CrimeSummary {
+    /// Builds the summary tables for `crimes`.
+    pub fn from_crimes(crimes: &[Crime]) -> Self {
+        let mut summary = Self::default();
+        for crime in crimes {
+            summary.total += 1;
+            *summary
+                .by_category
+                .entry(crime.category.clone())
+                .or_default() += 1;
+            *summary.by_month.entry(crime.month.clone()).or_default() += 1;
+            match &crime.location {
+                Some(location) => {
+                    *summary
+                        .by_street
+                        .entry(location.street.name.clone())
+                        .or_default() += 1;
+                }
+                None => summary.without_location += 1,
+            }
+            if let Some(location_type) = &crime.location_type {
+                *summary
+                    .by_location_type
+                    .entry(location_type.clone())
+                    .or_default() += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// A street with the number of crimes recorded on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hotspot {
+    /// Street identifier.
+    pub street_id: LocationId,
+    /// Approximate street name.
+    pub street_name: String,
+    /// Latitude of the first crime seen on the street.
+    pub latitude: String,
+    /// Longitude of the first crime seen on the street.
+    pub longitude: String,
+    /// Number of crimes.
+    pub count: usize,
+}
+
+/// Returns the `n` streets with the most crimes, most first.
+///
+/// Streets are grouped by ID rather than name, since generic names such as
+/// "On or near Parking Area" are shared by many streets. Ties are ordered by
+/// street ID.
+pub fn top_hotspots(crimes: &[Crime], n: usize) -> Vec<Hotspot> {
+    let mut streets: HashMap<LocationId, Hotspot> = HashMap::new();
+    for location in crimes.iter().filter_map(|crime| crime.location.as_ref()) {
+        streets
+            .entry(location.street.id)
+            .or_insert_with(|| Hotspot {
+                street_id: location.street.id,
+                street_name: location.street.name.clone(),
+                latitude: location.latitude.clone(),
+                longitude: location.longitude.clone(),
+                count: 0,
+            })
+            .count += 1;
+    }
+    let mut hotspots: Vec<Hotspot> = streets.into_values().collect();
+    hotspots.sort_by(|a, b| b.count.cmp(&a.count).then(a.street_id.cmp(&b.street_id)));
+    hotspots.truncate(n);
+    hotspots
+}
+
+/// Crime count for one month, with its change against earlier periods.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyTrend {
+    /// Month (format: `YYYY-MM`).
+    pub month: String,
+    /// Number of crimes in the month.
+    pub count: usize,
+    /// Mean count over this month and the preceding months in the window, or
+    /// `None` until a full window of months is available.
+    pub rolling_average: Option<f64>,
+    /// Percentage change against the previous month.
+    pub change_from_previous: Option<f64>,
+    /// Count for the same month a year earlier, if that month is in range.
+    pub previous_year_count: Option<usize>,
+    /// Percentage change against the same month a year earlier.
+    pub year_on_year_change: Option<f64>,
+}
+
+/// Builds a month-by-month trend from `crimes`.
+///
+/// Every month between the earliest and latest `month` is included, with a
+/// count of zero where no crimes were recorded. `window` is the number of
+/// months in the rolling average; a window of 0 is treated as 1. Crimes whose
+/// `month` is not in `YYYY-MM` format are left out.
+pub fn monthly_trend(crimes: &[Crime], window: usize) -> Vec<MonthlyTrend> {
+    let mut counts: BTreeMap<i32, usize> = BTreeMap::new();
+    for crime in crimes {
+        if let Some(index) = month_index(&crime.month) {
+            *counts.entry(index).or_default() += 1;
+        }
+    }
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Vec::new();
+    };
+
+    let window = window.max(1);
+    let series: Vec<usize> = (first..=last)
+        .map(|index| counts.get(&index).copied().unwrap_or(0))
+        .collect();
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let previous = i.checked_sub(1).map(|j| series[j]);
+            let previous_year_count = i.checked_sub(12).map(|j| series[j]);
+            let rolling_average = (i + 1 >= window)
+                .then(|| series[i + 1 - window..=i].iter().sum::<usize>() as f64 / window as f64);
+            MonthlyTrend {
+                month: format_month(first + i as i32),
+                count,
+                rolling_average,
+                change_from_previous: previous.and_then(|p| percent_change(p, count)),
+                previous_year_count,
+                year_on_year_change: previous_year_count.and_then(|p| percent_change(p, count)),
+            }
+        })
+        .collect()
+}
+
+/// Change in count for one category between two periods.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryChange {
+    /// Count in the current period.
+    pub current: usize,
+    /// Count in the previous period.
+    pub previous: usize,
+    /// Percentage change from the previous period.
+    pub change: Option<f64>,
+}
+
+/// Comparison of two sets of crimes, e.g. this quarter against the last, or
+/// this year against the same months a year earlier.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodComparison {
+    /// Totals across every category.
+    pub total: CategoryChange,
+    /// Changes by category slug. Includes categories seen in either period.
+    pub by_category: BTreeMap<String, CategoryChange>,
+}
+
+/// Compares crime counts in `current` against `previous`.
+pub fn compare_periods(current: &[Crime], previous: &[Crime]) -> PeriodComparison {
+    let current = CrimeSummary::from_crimes(current);
+    let previous = CrimeSummary::from_crimes(previous);
+    let change = |current: usize, previous: usize| CategoryChange {
+        current,
+        previous,
+        change: percent_change(previous, current),
+    };
+
+    let categories = current
+        .by_category
+        .keys()
+        .chain(previous.by_category.keys());
+    let by_category = categories
+        .map(|category| {
+            let count =
+                |summary: &CrimeSummary| summary.by_category.get(category).copied().unwrap_or(0);
+            (category.clone(), change(count(&current), count(&previous)))
+        })
+        .collect();
+
+    PeriodComparison {
+        total: change(current.total, previous.total),
+        by_category,
+    }
+}
+
+/// Percentage change from `previous` to `current`, or `None` if `previous`
+/// is zero.
+pub fn percent_change(previous: usize, current: usize) -> Option<f64> {
+    (previous > 0).then(|| (current as f64 - previous as f64) / previous as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crime(category: &str, month: &str, street: Option<(u64, &str)>) -> Crime {
+        let location = street.map(|(id, name)| {
+            serde_json::json!({
+                "latitude": "52.6",
+                "longitude": "-1.1",
+                "street": { "id": id, "name": name }
+            })
+        });
+        serde_json::from_value(serde_json::json!({
+            "category": category,
+            "persistent_id": "",
+            "location_subtype": "",
+            "id": 1,
+            "location": location,
+            "context": "",
+            "month": month,
+            "location_type": street.map(|_| "Force"),
+            "outcome_status": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summary_and_hotspots() {
+        let crimes = [
+            crime("burglary", "2024-01", Some((1, "On or near High Street"))),
+            crime("burglary", "2024-01", Some((1, "On or near High Street"))),
+            crime("drugs", "2024-02", Some((2, "On or near Parking Area"))),
+            crime("drugs", "2024-02", Some((3, "On or near Parking Area"))),
+            crime("drugs", "2024-02", None),
+        ];
+
+        let summary = CrimeSummary::from_crimes(&crimes);
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.by_category["drugs"], 3);
+        assert_eq!(summary.by_month["2024-01"], 2);
+        assert_eq!(summary.by_street["On or near Parking Area"], 2);
+        assert_eq!(summary.by_location_type["Force"], 4);
+        assert_eq!(summary.without_location, 1);
+
+        let hotspots = top_hotspots(&crimes, 2);
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].street_id, LocationId(1));
+        assert_eq!(hotspots[0].count, 2);
+        assert_eq!(hotspots[1].street_id, LocationId(2));
+    }
+
+    #[test]
+    fn test_monthly_trend() {
+        let mut crimes = vec![crime("drugs", "2023-01", None); 4];
+        crimes.extend(vec![crime("drugs", "2023-12", None); 2]);
+        crimes.extend(vec![crime("drugs", "2024-01", None); 3]);
+        crimes.push(crime("drugs", "unknown", None));
+
+        let trend = monthly_trend(&crimes, 2);
+        assert_eq!(trend.len(), 13);
+        assert_eq!(trend[0].month, "2023-01");
+        assert_eq!(trend[0].rolling_average, None);
+        assert_eq!(trend[1].count, 0);
+        assert_eq!(trend[1].change_from_previous, Some(-100.0));
+        assert_eq!(trend[2].change_from_previous, None);
+
+        let last = &trend[12];
+        assert_eq!(last.month, "2024-01");
+        assert_eq!(last.rolling_average, Some(2.5));
+        assert_eq!(last.change_from_previous, Some(50.0));
+        assert_eq!(last.previous_year_count, Some(4));
+        assert_eq!(last.year_on_year_change, Some(-25.0));
+    }
+
+    #[test]
+    fn test_compare_periods() {
+        let current = [
+            crime("burglary", "2024-02", None),
+            crime("burglary", "2024-02", None),
+            crime("robbery", "2024-02", None),
+        ];
+        let previous = [
+            crime("burglary", "2024-01", None),
+            crime("drugs", "2024-01", None),
+        ];
+
+        let comparison = compare_periods(&current, &previous);
+        assert_eq!(comparison.total.change, Some(50.0));
+        assert_eq!(comparison.by_category["burglary"].change, Some(100.0));
+        assert_eq!(comparison.by_category["drugs"].change, Some(-100.0));
+        assert_eq!(comparison.by_category["robbery"].previous, 0);
+        assert_eq!(comparison.by_category["robbery"].change, None);
+
+        let json = serde_json::to_value(&comparison).unwrap();
+        assert_eq!(json["total"]["current"], 3);
+    }
+}
@@ -0,0 +1,37 @@
+//! Aggregate statistics over records returned by the API.
+//!
+//! Everything here works on slices of models already fetched with the
+//! [`Client`](crate::Client); no requests are made.
+
+mod crimes;
+mod outcomes;
+mod spatial;
+mod stops;
+
+pub use crimes::{
+    CategoryChange, CrimeSummary, Hotspot, MonthlyTrend, PeriodComparison, compare_periods,
+    monthly_trend, percent_change, top_hotspots,
+};
+pub use outcomes::{OutcomeLifecycle, OutcomeStage, ResolutionFunnel, resolution_funnels};
+pub use spatial::{
+    Cluster, Clustering, DensityGrid, HotspotCell, Located, dbscan, getis_ord, haversine_distance,
+    kernel_density,
+};
+#[cfg(feature = "chrono")]
+pub use stops::stops_by_month_uk_local;
+pub use stops::{StopCounts, StopSummary, stops_by_force, stops_by_month};
+
+/// Months since year 0 for a `YYYY-MM` string.
+fn month_index(month: &str) -> Option<i32> {
+    let (year, month) = month.split_once('-')?;
+    if year.len() != 4 || month.len() != 2 {
+        return None;
+    }
+    let year: i32 = year.parse().ok()?;
+    let month: i32 = month.parse().ok()?;
+    (1..=12).contains(&month).then(|| year * 12 + month - 1)
+}
+
+fn format_month(index: i32) -> String {
+    format!("{:04}-{:02}", index / 12, index % 12 + 1)
+}
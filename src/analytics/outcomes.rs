@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::month_index;
+use crate::models::{CrimeOutcome, CrimeOutcomes, OutcomeCategory, OutcomeGroup};
+
+/// How far a crime has progressed through the justice system, in order of
+/// progression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutcomeStage {
+    /// Under investigation, or no outcome recorded yet. Unrecognised outcome
+    /// categories are also treated as open.
+    Open,
+    /// Investigation complete with no suspect identified.
+    NoSuspect,
+    /// A suspect was identified but no further action was taken (e.g. unable
+    /// to prosecute, not in the public interest).
+    NoFurtherAction,
+    /// Resolved without going to court: caution, penalty notice, drugs
+    /// warning or local resolution.
+    ResolvedOutOfCourt,
+    /// Charged or before the courts, including cases that ended without a
+    /// conviction.
+    Court,
+    /// The offender was convicted and sentenced.
+    Convicted,
+}
+
+impl OutcomeStage {
+    /// Returns the stage an outcome category belongs to.
+    pub fn of(category: &OutcomeCategory) -> Self {
+        match category.group() {
+            OutcomeGroup::InvestigationOngoing => Self::Open,
+            OutcomeGroup::NoFurtherAction if category.is_suspect_identified() => {
+                Self::NoFurtherAction
+            }
+            OutcomeGroup::NoFurtherAction => Self::NoSuspect,
+            OutcomeGroup::OutOfCourtDisposal => Self::ResolvedOutOfCourt,
+            OutcomeGroup::CourtOutcome | OutcomeGroup::Acquittal => Self::Court,
+            OutcomeGroup::Conviction => Self::Convicted,
+        }
+    }
+
+    /// Returns `true` if the stage implies a suspect was charged.
+    pub fn is_charged(&self) -> bool {
+        matches!(self, Self::Court | Self::Convicted)
+    }
+}
+
+/// Interpretation of the outcomes recorded against one crime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutcomeLifecycle {
+    /// Crime category slug (e.g. "burglary").
+    pub crime_category: String,
+    /// Month the crime was recorded (format: `YYYY-MM`).
+    pub crime_month: String,
+    /// Earliest outcome recorded.
+    pub first_outcome: Option<CrimeOutcome>,
+    /// Latest outcome recorded. Outcomes in the same month keep the order
+    /// returned by the API, so the last of them is taken.
+    pub final_outcome: Option<CrimeOutcome>,
+    /// Whole months from the crime to its first outcome.
+    pub months_to_first_outcome: Option<i32>,
+    /// Whole months from the crime to its final outcome.
+    pub months_to_final_outcome: Option<i32>,
+    /// Whether any outcome shows a suspect was charged.
+    pub charged: bool,
+    /// Stage of the final outcome, or [`OutcomeStage::Open`] if there is
+    /// none.
+    pub stage: OutcomeStage,
+}
+
+impl OutcomeLifecycle {
+    /// Interprets the outcomes returned by
+    /// [`Client::outcomes_for_crime`](crate::Client::outcomes_for_crime).
+    pub fn from_outcomes(outcomes: &CrimeOutcomes) -> Self {
+        let crime = &outcomes.crime;
+        let dated = || {
+            outcomes
+                .outcomes
+                .iter()
+                .map(|outcome| (month_index(&outcome.date), outcome))
+        };
+        // `min_by_key` keeps the first of equal keys and `max_by_key` the
+        // last, which preserves API order within a month. Outcomes with an
+        // unparseable date sort after dated ones for the first outcome and
+        // before them for the final one, so they are only picked when no
+        // outcome is dated.
+        let first_outcome = dated()
+            .min_by_key(|(month, _)| (month.is_none(), *month))
+            .map(|(_, o)| o);
+        let final_outcome = dated().max_by_key(|(month, _)| *month).map(|(_, o)| o);
+
+        let crime_month = month_index(&crime.month);
+        let months_to =
+            |outcome: Option<&CrimeOutcome>| Some(month_index(&outcome?.date)? - crime_month?);
+
+        Self {
+            crime_category: crime.category.clone(),
+            crime_month: crime.month.clone(),
+            months_to_first_outcome: months_to(first_outcome),
+            months_to_final_outcome: months_to(final_outcome),
+            charged: outcomes
+                .outcomes
+                .iter()
+                .any(|outcome| OutcomeStage::of(&outcome.category.code).is_charged()),
+            stage: final_outcome.map_or(OutcomeStage::Open, |outcome| {
+                OutcomeStage::of(&outcome.category.code)
+            }),
+            first_outcome: first_outcome.cloned(),
+            final_outcome: final_outcome.cloned(),
+        }
+    }
+}
+
+/// How many crimes reached each stage, for one crime category.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResolutionFunnel {
+    /// Number of crimes.
+    pub crimes: usize,
+    /// Crimes by the stage of their final outcome.
+    pub by_stage: BTreeMap<OutcomeStage, usize>,
+    /// Crimes where any outcome shows a suspect was charged.
+    pub charged: usize,
+    /// Mean whole months from crime to final outcome, over crimes that have
+    /// one.
+    pub mean_months_to_final_outcome: Option<f64>,
+}
+
+impl ResolutionFunnel {
+    /// Number of crimes whose final outcome is at `stage`.
+    pub fn count(&self, stage: OutcomeStage) -> usize {
+        self.by_stage.get(&stage).copied().unwrap_or(0)
+    }
+
+    /// Share of crimes that are no longer open, or `None` if there are no
+    /// crimes.
+    pub fn resolution_rate(&self) -> Option<f64> {
+        self.rate(self.crimes - self.count(OutcomeStage::Open))
+    }
+
+    /// Share of crimes where a suspect was charged, or `None` if there are
+    /// no crimes.
+    pub fn charge_rate(&self) -> Option<f64> {
+        self.rate(self.charged)
+    }
+
+    /// Share of crimes ending in a conviction, or `None` if there are no
+    /// crimes.
+    pub fn conviction_rate(&self) -> Option<f64> {
+        self.rate(self.count(OutcomeStage::Convicted))
+    }
+
+    fn rate(&self, count: usize) -> Option<f64> {
+        (self.crimes > 0).then(|| count as f64 / self.crimes as f64)
+    }
+}
+
+/// Builds a resolution funnel per crime category.
+pub fn resolution_funnels(crimes: &[CrimeOutcomes]) -> BTreeMap<String, ResolutionFunnel> {
+    let mut funnels: BTreeMap<String, (ResolutionFunnel, i64, usize)> = BTreeMap::new();
+    for outcomes in crimes {
+        let lifecycle = OutcomeLifecycle::from_outcomes(outcomes);
+        let (funnel, months_total, months_count) =
+            funnels.entry(lifecycle.crime_category).or_default();
+        funnel.crimes += 1;
+        *funnel.by_stage.entry(lifecycle.stage).or_default() += 1;
+        funnel.charged += usize::from(lifecycle.charged);
+        if let Some(months) = lifecycle.months_to_final_outcome {
+            *months_total += i64::from(months);
+            *months_count += 1;
+        }
+    }
+    funnels
+        .into_iter()
+        .map(|(category, (mut funnel, months_total, months_count))| {
+            funnel.mean_months_to_final_outcome =
+                (months_count > 0).then(|| months_total as f64 / months_count as f64);
+            (category, funnel)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crime_outcomes(category: &str, month: &str, outcomes: &[(&str, &str)]) -> CrimeOutcomes {
+        let outcomes: Vec<_> = outcomes
+            .iter()
+            .map(|(code, date)| {
+                serde_json::json!({
+                    "category": { "code": code, "name": "" },
+                    "date": date,
+                    "person_id": null
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "crime": {
+                "category": category,
+                "persistent_id": "",
+                "location_subtype": "",
+                "id": 1,
+                "location": null,
+                "context": "",
+                "month": month,
+                "location_type": null,
+                "outcome_status": null
+            },
+            "outcomes": outcomes
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let outcomes = crime_outcomes(
+            "burglary",
+            "2024-01",
+            &[
+                ("under-investigation", "2024-01"),
+                ("charged", "2024-03"),
+                ("sent-to-crown-court", "2024-03"),
+                ("imprisoned", "2024-07"),
+            ],
+        );
+        let lifecycle = OutcomeLifecycle::from_outcomes(&outcomes);
+        assert_eq!(lifecycle.months_to_first_outcome, Some(0));
+        assert_eq!(
+            lifecycle.first_outcome.unwrap().category.code,
+            OutcomeCategory::UnderInvestigation
+        );
+        assert_eq!(lifecycle.months_to_final_outcome, Some(6));
+        assert_eq!(
+            lifecycle.final_outcome.unwrap().category.code,
+            OutcomeCategory::Imprisoned
+        );
+        assert!(lifecycle.charged);
+        assert_eq!(lifecycle.stage, OutcomeStage::Convicted);
+
+        let same_month = crime_outcomes(
+            "drugs",
+            "2024-01",
+            &[("charged", "2024-02"), ("not-guilty", "2024-02")],
+        );
+        let lifecycle = OutcomeLifecycle::from_outcomes(&same_month);
+        assert_eq!(
+            lifecycle.final_outcome.unwrap().category.code,
+            OutcomeCategory::NotGuilty
+        );
+
+        let empty = OutcomeLifecycle::from_outcomes(&crime_outcomes("drugs", "2024-01", &[]));
+        assert_eq!(empty.stage, OutcomeStage::Open);
+        assert_eq!(empty.months_to_first_outcome, None);
+        assert!(!empty.charged);
+    }
+
+    #[test]
+    fn test_resolution_funnels() {
+        let crimes = [
+            crime_outcomes("burglary", "2024-01", &[("imprisoned", "2024-05")]),
+            crime_outcomes("burglary", "2024-01", &[("no-further-action", "2024-03")]),
+            crime_outcomes("burglary", "2024-02", &[]),
+            crime_outcomes("drugs", "2024-01", &[("cautioned", "2024-01")]),
+        ];
+
+        let funnels = resolution_funnels(&crimes);
+        let burglary = &funnels["burglary"];
+        assert_eq!(burglary.crimes, 3);
+        assert_eq!(burglary.count(OutcomeStage::NoSuspect), 1);
+        assert_eq!(burglary.charged, 1);
+        assert_eq!(burglary.mean_months_to_final_outcome, Some(3.0));
+        assert_eq!(burglary.resolution_rate(), Some(2.0 / 3.0));
+        assert_eq!(burglary.conviction_rate(), Some(1.0 / 3.0));
+        assert_eq!(funnels["drugs"].count(OutcomeStage::ResolvedOutOfCourt), 1);
+    }
+
+    #[test]
+    fn test_undated_outcomes_are_not_first() {
+        let outcomes = crime_outcomes(
+            "burglary",
+            "2024-01",
+            &[
+                ("under-investigation", "unknown"),
+                ("charged", "2024-03"),
+                ("imprisoned", "2024-07"),
+            ],
+        );
+        let lifecycle = OutcomeLifecycle::from_outcomes(&outcomes);
+        assert_eq!(
+            lifecycle.first_outcome.unwrap().category.code,
+            OutcomeCategory::Charged
+        );
+        assert_eq!(lifecycle.months_to_first_outcome, Some(2));
+        assert_eq!(
+            lifecycle.final_outcome.unwrap().category.code,
+            OutcomeCategory::Imprisoned
+        );
+
+        let undated = crime_outcomes("burglary", "2024-01", &[("charged", "")]);
+        let lifecycle = OutcomeLifecycle::from_outcomes(&undated);
+        assert_eq!(
+            lifecycle.first_outcome.unwrap().category.code,
+            OutcomeCategory::Charged
+        );
+        assert_eq!(lifecycle.months_to_first_outcome, None);
+    }
+}
@@ -0,0 +1,704 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use serde::Serialize;
+
+use crate::models::{BoundingBox, Coordinate, Crime, Location, StopAndSearch};
+
+/// Mean radius of the Earth in metres.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+const METRES_PER_DEGREE: f64 = EARTH_RADIUS * PI / 180.0;
+/// Kernel density contributions beyond this many bandwidths are ignored.
+const KERNEL_CUTOFF: f64 = 3.0;
+/// Gi* z-score for 95% confidence.
+const HOTSPOT_Z: f64 = 1.96;
+/// Most cells a density or hot spot grid may have.
+const MAX_GRID_CELLS: f64 = 1_000_000.0;
+
+/// A record with an approximate location, such as a crime or stop and
+/// search.
+pub trait Located {
+    /// Returns the record's location, if it has one.
+    fn location(&self) -> Option<&Location>;
+
+    /// Returns the record's parsed coordinate, if it has a location with
+    /// numeric latitude and longitude.
+    fn coordinate(&self) -> Option<Coordinate> {
+        self.location()?.coordinate()
+    }
+}
+
+impl Located for Location {
+    fn location(&self) -> Option<&Location> {
+        Some(self)
+    }
+}
+
+impl Located for Crime {
+    fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+impl Located for StopAndSearch {
+    fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+/// Great-circle distance between two coordinates in metres.
+pub fn haversine_distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    let (lat_a, lat_b) = (a.lat.to_radians(), b.lat.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lng = (b.lng - a.lng).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+/// A group of records found by [`dbscan`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cluster {
+    /// Indexes of the member records in the input slice, in ascending order.
+    pub members: Vec<usize>,
+    /// Mean position of the members.
+    pub centroid: Coordinate,
+}
+
+/// The result of [`dbscan`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Clustering {
+    /// Clusters, largest first. Ties are ordered by their first member.
+    pub clusters: Vec<Cluster>,
+    /// Cluster index for each input record, or `None` for noise and
+    /// records without a location.
+    pub labels: Vec<Option<usize>>,
+    /// Indexes of located records that belong to no cluster.
+    pub noise: Vec<usize>,
+    /// Indexes of records without a usable location.
+    pub unlocated: Vec<usize>,
+}
+
+/// Groups records with DBSCAN, using haversine distance.
+///
+/// A record is a core point if at least `min_points` records, counting
+/// itself, lie within `radius` metres. Clusters are the core points
+/// reachable from one another through chains of neighbours, plus the
+/// non-core records within `radius` of them.
+///
+/// Locations in the API are snapped to nearby map points, so many crimes
+/// share a coordinate; a `radius` below about 50 metres mostly finds those
+/// points rather than wider clusters.
+pub fn dbscan<T: Located>(records: &[T], radius: f64, min_points: usize) -> Clustering {
+    let mut unlocated = Vec::new();
+    let mut points = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        match record.coordinate() {
+            Some(coordinate) => points.push((index, coordinate)),
+            None => unlocated.push(index),
+        }
+    }
+
+    let index = NeighbourIndex::new(&points, radius);
+    let mut labels: Vec<Option<usize>> = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut clusters = 0;
+    for start in 0..points.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let neighbours = index.neighbours(&points, start, radius);
+        if neighbours.len() < min_points {
+            continue;
+        }
+        let cluster = clusters;
+        clusters += 1;
+        labels[start] = Some(cluster);
+        let mut queue = neighbours;
+        while let Some(point) = queue.pop() {
+            if labels[point].is_none() {
+                labels[point] = Some(cluster);
+            }
+            if !visited[point] {
+                visited[point] = true;
+                let neighbours = index.neighbours(&points, point, radius);
+                if neighbours.len() >= min_points {
+                    queue.extend(neighbours);
+                }
+            }
+        }
+    }
+
+    let mut members = vec![Vec::new(); clusters];
+    let mut noise = Vec::new();
+    for (&(index, _), label) in points.iter().zip(&labels) {
+        match label {
+            Some(cluster) => members[*cluster].push(index),
+            None => noise.push(index),
+        }
+    }
+    // Points are in input order, so each member list is already sorted.
+    let mut order: Vec<usize> = (0..clusters).collect();
+    order.sort_by(|&a, &b| {
+        members[b]
+            .len()
+            .cmp(&members[a].len())
+            .then(members[a][0].cmp(&members[b][0]))
+    });
+    let mut renumber = vec![0; clusters];
+    for (new, &old) in order.iter().enumerate() {
+        renumber[old] = new;
+    }
+
+    let mut record_labels = vec![None; records.len()];
+    for (&(index, _), label) in points.iter().zip(&labels) {
+        record_labels[index] = label.map(|cluster| renumber[cluster]);
+    }
+    let coordinates: HashMap<usize, &Coordinate> = points
+        .iter()
+        .map(|(index, point)| (*index, point))
+        .collect();
+    let clusters = order
+        .into_iter()
+        .map(|cluster| {
+            let members = std::mem::take(&mut members[cluster]);
+            let centroid = centroid(members.iter().map(|index| coordinates[index]));
+            Cluster { members, centroid }
+        })
+        .collect();
+
+    Clustering {
+        clusters,
+        labels: record_labels,
+        noise,
+        unlocated,
+    }
+}
+
+fn centroid<'a>(points: impl Iterator<Item = &'a Coordinate>) -> Coordinate {
+    let (mut lat, mut lng, mut n) = (0.0, 0.0, 0.0);
+    for point in points {
+        lat += point.lat;
+        lng += point.lng;
+        n += 1.0;
+    }
+    Coordinate {
+        lat: lat / n,
+        lng: lng / n,
+    }
+}
+
+/// Buckets points into cells at least `radius` metres across, so neighbours
+/// are found by checking adjacent cells only.
+struct NeighbourIndex {
+    lat_step: f64,
+    lng_step: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl NeighbourIndex {
+    fn new(points: &[(usize, Coordinate)], radius: f64) -> Self {
+        // A degree of longitude is shortest at the highest latitude, so size
+        // cells for that to keep them wide enough everywhere.
+        let max_lat = points
+            .iter()
+            .map(|(_, point)| point.lat.abs())
+            .fold(0.0, f64::max)
+            .min(89.0);
+        let lat_step = (radius / METRES_PER_DEGREE).max(f64::EPSILON);
+        let lng_step = lat_step / max_lat.to_radians().cos();
+        let mut index = Self {
+            lat_step,
+            lng_step,
+            cells: HashMap::new(),
+        };
+        for (position, (_, point)) in points.iter().enumerate() {
+            index
+                .cells
+                .entry(index.cell(point))
+                .or_default()
+                .push(position);
+        }
+        index
+    }
+
+    fn cell(&self, point: &Coordinate) -> (i64, i64) {
+        (
+            (point.lat / self.lat_step).floor() as i64,
+            (point.lng / self.lng_step).floor() as i64,
+        )
+    }
+
+    /// Positions of the points within `radius` of `points[position]`,
+    /// including itself.
+    fn neighbours(
+        &self,
+        points: &[(usize, Coordinate)],
+        position: usize,
+        radius: f64,
+    ) -> Vec<usize> {
+        let point = &points[position].1;
+        let (row, column) = self.cell(point);
+        let mut neighbours = Vec::new();
+        for d_row in -1..=1 {
+            for d_column in -1..=1 {
+                let Some(cell) = self.cells.get(&(row + d_row, column + d_column)) else {
+                    continue;
+                };
+                neighbours.extend(
+                    cell.iter()
+                        .copied()
+                        .filter(|&other| haversine_distance(point, &points[other].1) <= radius),
+                );
+            }
+        }
+        neighbours
+    }
+}
+
+/// A regular grid of cells roughly `cell_size` metres square.
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    bounds: BoundingBox,
+    rows: usize,
+    columns: usize,
+}
+
+impl Grid {
+    /// Covers `points`, extended by `padding` metres on every side.
+    ///
+    /// Returns `None` if there are no points, if `cell_size` is not
+    /// positive and finite, if `padding` is negative or not finite, or if
+    /// the grid would have more than [`MAX_GRID_CELLS`] cells.
+    fn cover(points: &[Coordinate], cell_size: f64, padding: f64) -> Option<Self> {
+        if !is_positive(cell_size) || padding < 0.0 || !padding.is_finite() {
+            return None;
+        }
+        let bounds = BoundingBox::from_points(points)?;
+        let scale = ((bounds.south + bounds.north) / 2.0).to_radians().cos();
+        let lat_step = (cell_size / METRES_PER_DEGREE).max(f64::EPSILON);
+        let lng_step = lat_step / scale;
+        let lat_padding = padding / METRES_PER_DEGREE;
+        let lng_padding = lat_padding / scale;
+        let south = bounds.south - lat_padding;
+        let west = bounds.west - lng_padding;
+        // Points on the far edge need a cell of their own.
+        let rows = ((bounds.north + lat_padding - south) / lat_step).floor() + 1.0;
+        let columns = ((bounds.east + lng_padding - west) / lng_step).floor() + 1.0;
+        let cells = rows * columns;
+        if !cells.is_finite() || cells > MAX_GRID_CELLS {
+            return None;
+        }
+        let (rows, columns) = (rows as usize, columns as usize);
+        Some(Self {
+            bounds: BoundingBox {
+                south,
+                west,
+                north: south + rows as f64 * lat_step,
+                east: west + columns as f64 * lng_step,
+            },
+            rows,
+            columns,
+        })
+    }
+
+    fn from_bounds(bounds: BoundingBox, rows: usize, columns: usize) -> Self {
+        Self {
+            bounds,
+            rows,
+            columns,
+        }
+    }
+
+    fn lat_step(&self) -> f64 {
+        (self.bounds.north - self.bounds.south) / self.rows as f64
+    }
+
+    fn lng_step(&self) -> f64 {
+        (self.bounds.east - self.bounds.west) / self.columns as f64
+    }
+
+    fn cell_of(&self, point: &Coordinate) -> (usize, usize) {
+        let row = ((point.lat - self.bounds.south) / self.lat_step()).floor() as usize;
+        let column = ((point.lng - self.bounds.west) / self.lng_step()).floor() as usize;
+        (row.min(self.rows - 1), column.min(self.columns - 1))
+    }
+
+    fn cell_bounds(&self, row: usize, column: usize) -> BoundingBox {
+        let south = self.bounds.south + row as f64 * self.lat_step();
+        let west = self.bounds.west + column as f64 * self.lng_step();
+        BoundingBox {
+            south,
+            west,
+            north: south + self.lat_step(),
+            east: west + self.lng_step(),
+        }
+    }
+
+    fn centre(&self, row: usize, column: usize) -> Coordinate {
+        Coordinate {
+            lat: self.bounds.south + (row as f64 + 0.5) * self.lat_step(),
+            lng: self.bounds.west + (column as f64 + 0.5) * self.lng_step(),
+        }
+    }
+
+    /// Cells whose rows and columns are within `reach` of `(row, column)`.
+    fn around(
+        &self,
+        row: usize,
+        column: usize,
+        reach: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let rows =
+            row.saturating_sub(reach)..row.saturating_add(reach).saturating_add(1).min(self.rows);
+        let columns = column.saturating_sub(reach)
+            ..column
+                .saturating_add(reach)
+                .saturating_add(1)
+                .min(self.columns);
+        rows.flat_map(move |row| columns.clone().map(move |column| (row, column)))
+    }
+}
+
+/// Returns `true` if `value` is a usable size or distance: positive and
+/// finite.
+fn is_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Located records of `records`, parsed.
+fn coordinates<T: Located>(records: &[T]) -> Vec<Coordinate> {
+    records.iter().filter_map(Located::coordinate).collect()
+}
+
+/// A kernel density surface built by [`kernel_density`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DensityGrid {
+    /// Area covered by the grid.
+    pub bounds: BoundingBox,
+    /// Number of rows, from south to north.
+    pub rows: usize,
+    /// Number of columns, from west to east.
+    pub columns: usize,
+    /// Estimated records per square kilometre at each cell centre, row by
+    /// row from the south-west corner.
+    pub values: Vec<f64>,
+}
+
+impl DensityGrid {
+    /// Density at a cell.
+    pub fn value(&self, row: usize, column: usize) -> f64 {
+        self.values[row * self.columns + column]
+    }
+
+    /// Centre of a cell.
+    pub fn centre(&self, row: usize, column: usize) -> Coordinate {
+        self.grid().centre(row, column)
+    }
+
+    /// Returns up to `n` local maxima, densest first, as cell centres with
+    /// their density. A cell is a local maximum if no neighbouring cell is
+    /// denser; ties between neighbours are kept.
+    pub fn peaks(&self, n: usize) -> Vec<(Coordinate, f64)> {
+        let grid = self.grid();
+        let mut peaks = Vec::new();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let value = self.value(row, column);
+                if value > 0.0
+                    && grid
+                        .around(row, column, 1)
+                        .all(|(r, c)| self.value(r, c) <= value)
+                {
+                    peaks.push((grid.centre(row, column), value));
+                }
+            }
+        }
+        peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+        peaks.truncate(n);
+        peaks
+    }
+
+    fn grid(&self) -> Grid {
+        Grid::from_bounds(self.bounds, self.rows, self.columns)
+    }
+}
+
+/// Estimates the density of records on a grid of cells `cell_size` metres
+/// across, with a Gaussian kernel of standard deviation `bandwidth` metres.
+///
+/// The grid covers every located record with one bandwidth to spare on each
+/// side. Returns `None` if no record has a usable location, if `cell_size`
+/// or `bandwidth` is not positive and finite, or if the grid would have more
+/// than a million cells.
+pub fn kernel_density<T: Located>(
+    records: &[T],
+    cell_size: f64,
+    bandwidth: f64,
+) -> Option<DensityGrid> {
+    if !is_positive(bandwidth) {
+        return None;
+    }
+    let points = coordinates(records);
+    let grid = Grid::cover(&points, cell_size, bandwidth)?;
+    let mut values = vec![0.0; grid.rows * grid.columns];
+    let reach = (KERNEL_CUTOFF * bandwidth / cell_size).ceil() as usize;
+    // Normalised so the surface integrates to one per record, in km².
+    let scale = 1e6 / (2.0 * PI * bandwidth * bandwidth);
+    for point in &points {
+        let (row, column) = grid.cell_of(point);
+        for (row, column) in grid.around(row, column, reach) {
+            let distance = haversine_distance(point, &grid.centre(row, column));
+            if distance <= KERNEL_CUTOFF * bandwidth {
+                values[row * grid.columns + column] +=
+                    scale * (-distance * distance / (2.0 * bandwidth * bandwidth)).exp();
+            }
+        }
+    }
+    Some(DensityGrid {
+        bounds: grid.bounds,
+        rows: grid.rows,
+        columns: grid.columns,
+        values,
+    })
+}
+
+/// A grid cell scored by [`getis_ord`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HotspotCell {
+    /// Area covered by the cell.
+    pub bounds: BoundingBox,
+    /// Centre of the cell.
+    pub centre: Coordinate,
+    /// Number of records in the cell.
+    pub count: usize,
+    /// Getis-Ord Gi* z-score. Large positive values mean the cell and its
+    /// neighbours hold more records than chance would suggest.
+    pub z_score: f64,
+}
+
+impl HotspotCell {
+    /// Returns `true` if the cell is a hot spot at 95% confidence.
+    pub fn is_hot(&self) -> bool {
+        self.z_score >= HOTSPOT_Z
+    }
+
+    /// Returns `true` if the cell is a cold spot at 95% confidence.
+    pub fn is_cold(&self) -> bool {
+        self.z_score <= -HOTSPOT_Z
+    }
+}
+
+/// Scores grid cells `cell_size` metres across with the Getis-Ord Gi*
+/// statistic.
+///
+/// Records are counted per cell over a grid covering every located record.
+/// Each cell's neighbourhood is itself plus the cells whose centres lie
+/// within `distance` metres of its centre, so cells next to a hot spot
+/// score highly too. Cells are returned highest z-score first, then most
+/// records first; if every cell holds the same count, all scores are zero.
+///
+/// Returns no cells if no record has a usable location, if `cell_size` or
+/// `distance` is not positive and finite, or if the grid would have more
+/// than a million cells.
+pub fn getis_ord<T: Located>(records: &[T], cell_size: f64, distance: f64) -> Vec<HotspotCell> {
+    if !is_positive(distance) {
+        return Vec::new();
+    }
+    let points = coordinates(records);
+    let Some(grid) = Grid::cover(&points, cell_size, 0.0) else {
+        return Vec::new();
+    };
+    let mut counts = vec![0usize; grid.rows * grid.columns];
+    for point in &points {
+        let (row, column) = grid.cell_of(point);
+        counts[row * grid.columns + column] += 1;
+    }
+
+    let n = counts.len() as f64;
+    let mean = points.len() as f64 / n;
+    let variance = counts
+        .iter()
+        .map(|&count| (count as f64).powi(2))
+        .sum::<f64>()
+        / n
+        - mean * mean;
+    let deviation = variance.max(0.0).sqrt();
+    // No neighbour is further than the grid is wide.
+    let reach = ((distance / cell_size).ceil() as usize).min(grid.rows.max(grid.columns));
+
+    let mut cells = Vec::with_capacity(counts.len());
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            let centre = grid.centre(row, column);
+            let (mut weights, mut sum) = (0.0, 0.0);
+            for (r, c) in grid.around(row, column, reach) {
+                if (r, c) == (row, column)
+                    || haversine_distance(&centre, &grid.centre(r, c)) <= distance
+                {
+                    weights += 1.0;
+                    sum += counts[r * grid.columns + c] as f64;
+                }
+            }
+            // Binary weights, so the sum of squared weights is `weights`.
+            let denominator = deviation
+                * ((n * weights - weights * weights) / (n - 1.0))
+                    .max(0.0)
+                    .sqrt();
+            let z_score = if denominator > 0.0 {
+                (sum - mean * weights) / denominator
+            } else {
+                0.0
+            };
+            cells.push(HotspotCell {
+                bounds: grid.cell_bounds(row, column),
+                centre,
+                count: counts[row * grid.columns + column],
+                z_score,
+            });
+        }
+    }
+    cells.sort_by(|a, b| b.z_score.total_cmp(&a.z_score).then(b.count.cmp(&a.count)));
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crime(location: Option<(f64, f64)>) -> Crime {
+        let location = location.map(|(lat, lng)| {
+            serde_json::json!({
+                "latitude": lat.to_string(),
+                "longitude": lng.to_string(),
+                "street": { "id": 1, "name": "On or near High Street" }
+            })
+        });
+        serde_json::from_value(serde_json::json!({
+            "category": "burglary",
+            "persistent_id": "",
+            "location_subtype": "",
+            "id": 1,
+            "location": location,
+            "context": "",
+            "month": "2024-01",
+            "location_type": "Force",
+            "outcome_status": null
+        }))
+        .unwrap()
+    }
+
+    /// Two groups near Leicester, a lone crime and one without a location.
+    fn crimes() -> Vec<Crime> {
+        vec![
+            crime(Some((52.6300, -1.1300))),
+            crime(Some((52.6400, -1.1000))),
+            crime(Some((52.6302, -1.1302))),
+            crime(Some((52.6301, -1.1299))),
+            crime(None),
+            crime(Some((52.6700, -1.2000))),
+            crime(Some((52.6401, -1.1001))),
+            crime(Some((52.6399, -1.1000))),
+            crime(Some((52.6400, -1.0999))),
+        ]
+    }
+
+    #[test]
+    fn test_haversine_distance() {
+        let a = Coordinate { lat: 0.0, lng: 0.0 };
+        let b = Coordinate { lat: 1.0, lng: 0.0 };
+        assert!((haversine_distance(&a, &b) - METRES_PER_DEGREE).abs() < 1e-6);
+        assert!((METRES_PER_DEGREE - 111_195.0).abs() < 1.0);
+        let c = Coordinate {
+            lat: 60.0,
+            lng: 1.0,
+        };
+        let d = Coordinate {
+            lat: 60.0,
+            lng: 0.0,
+        };
+        // A degree of longitude at 60° is about half as long.
+        assert!((haversine_distance(&c, &d) / METRES_PER_DEGREE - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dbscan() {
+        let clustering = dbscan(&crimes(), 50.0, 3);
+        assert_eq!(clustering.clusters.len(), 2);
+        assert_eq!(clustering.clusters[0].members, [1, 6, 7, 8]);
+        assert_eq!(clustering.clusters[1].members, [0, 2, 3]);
+        let centroid = &clustering.clusters[0].centroid;
+        assert!((centroid.lat - 52.64).abs() < 1e-4);
+        assert!((centroid.lng + 1.1).abs() < 1e-4);
+        assert_eq!(clustering.noise, [5]);
+        assert_eq!(clustering.unlocated, [4]);
+        assert_eq!(
+            clustering.labels,
+            [
+                Some(1),
+                Some(0),
+                Some(1),
+                Some(1),
+                None,
+                None,
+                Some(0),
+                Some(0),
+                Some(0)
+            ]
+        );
+
+        // Too few neighbours anywhere.
+        let clustering = dbscan(&crimes(), 50.0, 5);
+        assert!(clustering.clusters.is_empty());
+        assert_eq!(clustering.noise.len(), 8);
+    }
+
+    #[test]
+    fn test_kernel_density() {
+        let density = kernel_density(&crimes(), 100.0, 200.0).unwrap();
+        assert_eq!(density.values.len(), density.rows * density.columns);
+        let peaks = density.peaks(3);
+        assert_eq!(peaks.len(), 3);
+        // The group of four is densest, then the group of three.
+        let near = |peak: &(Coordinate, f64), lat, lng| {
+            haversine_distance(&peak.0, &Coordinate { lat, lng }) < 100.0
+        };
+        assert!(near(&peaks[0], 52.64, -1.10));
+        assert!(near(&peaks[1], 52.63, -1.13));
+        assert!(near(&peaks[2], 52.67, -1.20));
+        assert!(peaks[0].1 > peaks[1].1 && peaks[1].1 > peaks[2].1);
+
+        assert!(kernel_density(&[crime(None)], 100.0, 200.0).is_none());
+        for (cell_size, bandwidth) in [(0.0, 200.0), (100.0, -1.0), (f64::NAN, 200.0)] {
+            assert!(kernel_density(&crimes(), cell_size, bandwidth).is_none());
+        }
+        // A grid of more than a million cells is refused.
+        assert!(kernel_density(&crimes(), 1e-3, 200.0).is_none());
+    }
+
+    #[test]
+    fn test_getis_ord() {
+        let cells = getis_ord(&crimes(), 500.0, 600.0);
+        let hottest = &cells[0];
+        assert_eq!(hottest.count, 4);
+        assert!(hottest.is_hot());
+        assert!(hottest.bounds.contains(52.64, -1.10));
+        assert_eq!(cells.iter().map(|cell| cell.count).sum::<usize>(), 8);
+        assert!(cells.iter().all(|cell| !cell.is_cold()));
+        assert!(cells.last().unwrap().z_score < 0.0);
+
+        assert!(getis_ord(&[crime(None)], 500.0, 600.0).is_empty());
+        let single = getis_ord(&[crime(Some((52.6, -1.1)))], 500.0, 600.0);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].z_score, 0.0);
+
+        for (cell_size, distance) in [(0.0, 600.0), (500.0, f64::INFINITY), (1e-3, 600.0)] {
+            assert!(getis_ord(&crimes(), cell_size, distance).is_empty());
+        }
+
+        // A distance far wider than the grid makes every cell a neighbour.
+        let cells = getis_ord(&crimes(), 500.0, 1e25);
+        assert_eq!(cells.iter().map(|cell| cell.count).sum::<usize>(), 8);
+        assert!(cells.iter().all(|cell| cell.z_score == 0.0));
+    }
+}
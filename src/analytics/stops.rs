@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+
+use crate::models::{
+    AgeRange, Ethnicity, ForceId, Gender, Legislation, ObjectOfSearch, SelfDefinedEthnicity,
+    StopAndSearch, StopOutcome,
+};
+
+/// Search, find and arrest counts for a group of stops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StopCounts {
+    /// Number of stops.
+    pub searches: usize,
+    /// Stops with a positive outcome (anything other than no further action).
+    pub finds: usize,
+    /// Stops that resulted in an arrest.
+    pub arrests: usize,
+    /// Stops with a positive outcome linked to the object of the search.
+    pub linked_to_object: usize,
+}
+
+impl StopCounts {
+    fn add(&mut self, stop: &StopAndSearch) {
+        let outcome = stop.stop_outcome();
+        self.searches += 1;
+        self.finds += usize::from(outcome.is_positive());
+        self.arrests += usize::from(outcome.is_arrest());
+        self.linked_to_object += usize::from(stop.is_outcome_linked_to_object());
+    }
+
+    /// Share of searches with a positive outcome, or `None` if there were no
+    /// searches.
+    pub fn find_rate(&self) -> Option<f64> {
+        self.rate(self.finds)
+    }
+
+    /// Share of searches that resulted in an arrest, or `None` if there were
+    /// no searches.
+    pub fn arrest_rate(&self) -> Option<f64> {
+        self.rate(self.arrests)
+    }
+
+    /// Share of searches with a positive outcome linked to the object of the
+    /// search, or `None` if there were no searches.
+    pub fn linked_to_object_rate(&self) -> Option<f64> {
+        self.rate(self.linked_to_object)
+    }
+
+    fn rate(&self, count: usize) -> Option<f64> {
+        (self.searches > 0).then(|| count as f64 / self.searches as f64)
+    }
+}
+
+/// Aggregate tables for a set of stop and search records.
+///
+/// Breakdowns are keyed by `None` for stops where the field was not
+/// recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StopSummary {
+    /// Counts across every stop.
+    pub overall: StopCounts,
+    /// Counts by legislation.
+    pub by_legislation: BTreeMap<Option<Legislation>, StopCounts>,
+    /// Counts by object of search.
+    pub by_object_of_search: BTreeMap<Option<ObjectOfSearch>, StopCounts>,
+    /// Counts by age range.
+    pub by_age_range: BTreeMap<Option<AgeRange>, StopCounts>,
+    /// Counts by gender.
+    pub by_gender: BTreeMap<Option<Gender>, StopCounts>,
+    /// Counts by officer-defined ethnicity.
+    pub by_officer_defined_ethnicity: BTreeMap<Option<Ethnicity>, StopCounts>,
+    /// Counts by self-defined ethnicity.
+    pub by_self_defined_ethnicity: BTreeMap<Option<SelfDefinedEthnicity>, StopCounts>,
+    /// Number of stops per outcome.
+    pub by_outcome: BTreeMap<StopOutcome, usize>,
+    /// Counts by hour of day. Index 0 is midnight to 1am. Stops without a
+    /// parseable `datetime` are left out.
+    ///
+    /// Hours are read from the timestamp as returned by the API, unless the
+    /// summary was built with [`StopSummary::from_stops_uk_local`].
+    pub by_hour: [StopCounts; 24],
+}
+
+impl StopSummary {
+    /// Builds the summary tables for `stops`.
+    pub fn from_stops(stops: &[StopAndSearch]) -> Self {
+        Self::with_clock(stops, Clock::Api)
+    }
+
+    /// Builds the summary tables for `stops`, with hours in UK local time
+    /// (GMT or BST).
+    #[cfg(feature = "chrono")]
+    pub fn from_stops_uk_local(stops: &[StopAndSearch]) -> Self {
+        Self::with_clock(stops, Clock::UkLocal)
+    }
+
+    fn with_clock(stops: &[StopAndSearch], clock: Clock) -> Self {
+        let mut summary = Self::default();
+        for stop in stops {
+            summary.add(stop, clock);
+        }
+        summary
+    }
+
+    fn add(&mut self, stop: &StopAndSearch, clock: Clock) {
+        self.overall.add(stop);
+        tally(&mut self.by_legislation, &stop.legislation, stop);
+        tally(&mut self.by_object_of_search, &stop.object_of_search, stop);
+        tally(&mut self.by_age_range, &stop.age_range, stop);
+        tally(&mut self.by_gender, &stop.gender, stop);
+        tally(
+            &mut self.by_officer_defined_ethnicity,
+            &stop.officer_defined_ethnicity,
+            stop,
+        );
+        tally(
+            &mut self.by_self_defined_ethnicity,
+            &stop.self_defined_ethnicity,
+            stop,
+        );
+        *self.by_outcome.entry(stop.stop_outcome()).or_default() += 1;
+        if let Some(hour) = clock.hour(stop) {
+            self.by_hour[hour as usize].add(stop);
+        }
+    }
+}
+
+/// Builds a summary per month (format: `YYYY-MM`), taken from each stop's
+/// `datetime` as returned by the API. Stops without a parseable `datetime`
+/// are left out.
+pub fn stops_by_month(stops: &[StopAndSearch]) -> BTreeMap<String, StopSummary> {
+    by_month(stops, Clock::Api)
+}
+
+/// Like [`stops_by_month`], but with months and hours in UK local time (GMT
+/// or BST). A stop at 23:30 UTC on the last day of a summer month counts
+/// towards the next month.
+#[cfg(feature = "chrono")]
+pub fn stops_by_month_uk_local(stops: &[StopAndSearch]) -> BTreeMap<String, StopSummary> {
+    by_month(stops, Clock::UkLocal)
+}
+
+fn by_month(stops: &[StopAndSearch], clock: Clock) -> BTreeMap<String, StopSummary> {
+    let mut months: BTreeMap<String, StopSummary> = BTreeMap::new();
+    for stop in stops {
+        if let Some(month) = clock.month(stop) {
+            months.entry(month).or_default().add(stop, clock);
+        }
+    }
+    months
+}
+
+/// Builds a summary per force from stops fetched force by force, e.g. with
+/// [`Client::stops_force`](crate::Client::stops_force). Stops for the same
+/// force are combined.
+pub fn stops_by_force<'a, I>(forces: I) -> BTreeMap<ForceId, StopSummary>
+where
+    I: IntoIterator<Item = (ForceId, &'a [StopAndSearch])>,
+{
+    let mut summaries: BTreeMap<ForceId, StopSummary> = BTreeMap::new();
+    for (force, stops) in forces {
+        let summary = summaries.entry(force).or_default();
+        for stop in stops {
+            summary.add(stop, Clock::Api);
+        }
+    }
+    summaries
+}
+
+fn tally<K: Ord + Clone>(
+    table: &mut BTreeMap<Option<K>, StopCounts>,
+    key: &Option<K>,
+    stop: &StopAndSearch,
+) {
+    table.entry(key.clone()).or_default().add(stop);
+}
+
+/// Which clock hours and months are read from.
+#[derive(Clone, Copy)]
+enum Clock {
+    /// The timestamp as returned by the API.
+    Api,
+    /// UK local time (GMT or BST).
+    #[cfg(feature = "chrono")]
+    UkLocal,
+}
+
+impl Clock {
+    fn hour(self, stop: &StopAndSearch) -> Option<u32> {
+        match self {
+            Self::Api => {
+                // e.g. "2024-01-15T12:30:00+00:00"
+                let datetime = stop.datetime.as_deref()?;
+                let hour: u32 = datetime.get(11..13)?.parse().ok()?;
+                (hour < 24).then_some(hour)
+            }
+            #[cfg(feature = "chrono")]
+            Self::UkLocal => stop.hour_of_day(),
+        }
+    }
+
+    fn month(self, stop: &StopAndSearch) -> Option<String> {
+        match self {
+            Self::Api => {
+                let month = stop.datetime.as_deref()?.get(..7)?;
+                let bytes = month.as_bytes();
+                let valid = bytes[4] == b'-'
+                    && bytes
+                        .iter()
+                        .enumerate()
+                        .all(|(i, b)| i == 4 || b.is_ascii_digit());
+                valid.then(|| month.to_string())
+            }
+            #[cfg(feature = "chrono")]
+            Self::UkLocal => {
+                use chrono::Datelike;
+
+                let local = crate::datetime::to_uk_local(stop.parsed_datetime()?);
+                Some(format!("{:04}-{:02}", local.year(), local.month()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(
+        datetime: &str,
+        legislation: &str,
+        gender: &str,
+        outcome: Option<&str>,
+        linked: bool,
+    ) -> StopAndSearch {
+        serde_json::from_value(serde_json::json!({
+            "type": "Person search",
+            "involved_person": true,
+            "datetime": datetime,
+            "operation": false,
+            "operation_name": null,
+            "location": null,
+            "gender": gender,
+            "age_range": "18-24",
+            "self_defined_ethnicity": null,
+            "officer_defined_ethnicity": "White",
+            "legislation": legislation,
+            "object_of_search": "Controlled drugs",
+            "outcome": outcome.map_or(serde_json::json!(false), |o| serde_json::json!(o)),
+            "outcome_linked_to_object_of_search": linked,
+            "removal_of_more_than_outer_clothing": false
+        }))
+        .unwrap()
+    }
+
+    fn sample() -> Vec<StopAndSearch> {
+        vec![
+            stop(
+                "2024-01-15T12:30:00+00:00",
+                "Misuse of Drugs Act 1971 (section 23)",
+                "Male",
+                Some("Arrest"),
+                true,
+            ),
+            stop(
+                "2024-01-20T12:10:00+00:00",
+                "Misuse of Drugs Act 1971 (section 23)",
+                "Female",
+                Some("Community resolution"),
+                false,
+            ),
+            stop(
+                "2024-02-02T22:45:00+00:00",
+                "Police and Criminal Evidence Act 1984 (section 1)",
+                "Male",
+                None,
+                false,
+            ),
+            stop(
+                "2024-02-03T08:00:00+00:00",
+                "Misuse of Drugs Act 1971 (section 23)",
+                "Male",
+                Some("A no further action disposal"),
+                false,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_summary_tables() {
+        let summary = StopSummary::from_stops(&sample());
+
+        assert_eq!(
+            summary.overall,
+            StopCounts {
+                searches: 4,
+                finds: 2,
+                arrests: 1,
+                linked_to_object: 1,
+            }
+        );
+        assert_eq!(summary.overall.find_rate(), Some(0.5));
+        assert_eq!(summary.overall.arrest_rate(), Some(0.25));
+        assert_eq!(StopCounts::default().find_rate(), None);
+
+        let drugs = &summary.by_legislation[&Some(Legislation::MisuseOfDrugsAct1971Section23)];
+        assert_eq!(drugs.searches, 3);
+        assert_eq!(drugs.arrests, 1);
+        assert_eq!(summary.by_gender[&Some(Gender::Male)].searches, 3);
+        assert_eq!(summary.by_gender[&Some(Gender::Female)].finds, 1);
+        assert_eq!(
+            summary.by_age_range[&Some(AgeRange::From18To24)].searches,
+            4
+        );
+        assert_eq!(summary.by_self_defined_ethnicity[&None].searches, 4);
+        assert_eq!(summary.by_outcome[&StopOutcome::NoFurtherAction], 2);
+
+        assert_eq!(summary.by_hour[12].searches, 2);
+        assert_eq!(summary.by_hour[22].searches, 1);
+        assert_eq!(summary.by_hour[8].searches, 1);
+    }
+
+    #[test]
+    fn test_grouping_by_month_and_force() {
+        let stops = sample();
+
+        let months = stops_by_month(&stops);
+        assert_eq!(months.keys().collect::<Vec<_>>(), ["2024-01", "2024-02"]);
+        assert_eq!(months["2024-01"].overall.finds, 2);
+        assert_eq!(months["2024-02"].overall.finds, 0);
+
+        let (leicestershire, kent): (ForceId, ForceId) =
+            ("leicestershire".parse().unwrap(), "kent".parse().unwrap());
+        let forces = stops_by_force([
+            (leicestershire.clone(), &stops[..2]),
+            (kent.clone(), &stops[2..]),
+            (leicestershire.clone(), &stops[3..]),
+        ]);
+        assert_eq!(forces[&leicestershire].overall.searches, 3);
+        assert_eq!(forces[&kent].overall.searches, 2);
+    }
+
+    #[test]
+    fn test_summer_stops_use_api_time() {
+        // 00:30 on 1 July in BST.
+        let stops = [stop(
+            "2024-06-30T23:30:00+00:00",
+            "Misuse of Drugs Act 1971 (section 23)",
+            "Male",
+            None,
+            false,
+        )];
+        assert_eq!(StopSummary::from_stops(&stops).by_hour[23].searches, 1);
+        assert_eq!(
+            stops_by_month(&stops).keys().collect::<Vec<_>>(),
+            ["2024-06"]
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_summer_stops_in_uk_local_time() {
+        let stops = [stop(
+            "2024-06-30T23:30:00+00:00",
+            "Misuse of Drugs Act 1971 (section 23)",
+            "Male",
+            None,
+            false,
+        )];
+        assert_eq!(
+            StopSummary::from_stops_uk_local(&stops).by_hour[0].searches,
+            1
+        );
+        let months = stops_by_month_uk_local(&stops);
+        assert_eq!(months.keys().collect::<Vec<_>>(), ["2024-07"]);
+        assert_eq!(months["2024-07"].by_hour[0].searches, 1);
+    }
+}
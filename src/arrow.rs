@@ -0,0 +1,567 @@
+//! Apache Arrow and Parquet export, available with the `arrow` feature.
+//!
+//! Each exported model implements [`ToArrow`], which defines a flat, stable
+//! schema: nested fields such as `location` and `outcome_status` become
+//! prefixed columns, coordinates are parsed to `Float64`, and enum values
+//! are written as the strings the API uses. The schemas carry a
+//! `uk_police_api.schema_version` metadata entry, bumped whenever a column
+//! is renamed, removed or changes type.
+//!
+//! | Model | Columns |
+//! |-------|---------|
+//! | [`Crime`] | `id`, `persistent_id`, `category`, `month`, `location_type`, `location_subtype`, `context`, `latitude`, `longitude`, `street_id`, `street_name`, `outcome_category`, `outcome_date` |
+//! | [`Outcome`] | `category`, `category_name`, `date`, `person_id`, `crime_id`, `crime_persistent_id`, `crime_category`, `crime_month`, `latitude`, `longitude`, `street_id`, `street_name` |
+//! | [`StopAndSearch`] | `type`, `involved_person`, `datetime`, `month`, `operation`, `operation_name`, `latitude`, `longitude`, `street_id`, `street_name`, `gender`, `age_range`, `self_defined_ethnicity`, `officer_defined_ethnicity`, `legislation`, `object_of_search`, `outcome`, `outcome_linked_to_object_of_search`, `removal_of_more_than_outer_clothing`, `outcome_object_id`, `outcome_object_name` |
+//! | [`Force`] | `id`, `name` |
+//! | [`Neighbourhood`] | `id`, `name` |
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = uk_police_api::Client::new();
+//! let force = "leicestershire".parse()?;
+//! let stops = client.stops_force(&force, Some("2024-01")).await?;
+//!
+//! // Writes exports/month=2024-01/force=leicestershire/part-0.parquet
+//! uk_police_api::arrow::write_partitioned("exports", &force, &stops)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+
+use crate::models::{Crime, Force, ForceId, Location, Neighbourhood, Outcome, StopAndSearch};
+
+/// Version of the exported schemas, stored in each schema's metadata.
+pub const SCHEMA_VERSION: &str = "1";
+
+/// Directory name used for records without a month, following the Hive
+/// convention for null partition values.
+pub const DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Error returned when exporting records.
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A model that can be exported as rows of an Arrow [`RecordBatch`].
+pub trait ToArrow: Sized {
+    /// The schema of the exported rows.
+    fn schema() -> SchemaRef;
+
+    /// Builds one array per field of [`ToArrow::schema`], in order.
+    fn columns(records: &[&Self]) -> Vec<ArrayRef>;
+
+    /// The month (`YYYY-MM`) used to partition the record, if it has one.
+    fn month(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Converts records into a single [`RecordBatch`].
+pub fn record_batch<T: ToArrow>(records: &[T]) -> Result<RecordBatch, ExportError> {
+    batch(&records.iter().collect::<Vec<_>>())
+}
+
+/// Writes records as a single Snappy-compressed Parquet file.
+pub fn write_parquet<T: ToArrow>(
+    writer: impl Write + Send,
+    records: &[T],
+) -> Result<(), ExportError> {
+    write_batch(writer, &record_batch(records)?)
+}
+
+/// Writes records as Parquet files under `dir`, partitioned Hive-style as
+/// `month=YYYY-MM/force=<force>/part-0.parquet`, and returns the paths
+/// written.
+///
+/// Each partition file is replaced, so exporting the same month again
+/// overwrites the earlier export rather than duplicating it.
+pub fn write_partitioned<T: ToArrow>(
+    dir: impl AsRef<Path>,
+    force: &ForceId,
+    records: &[T],
+) -> Result<Vec<PathBuf>, ExportError> {
+    let mut partitions: BTreeMap<&str, Vec<&T>> = BTreeMap::new();
+    for record in records {
+        let month = record.month().unwrap_or(DEFAULT_PARTITION);
+        partitions.entry(month).or_default().push(record);
+    }
+
+    let mut paths = Vec::with_capacity(partitions.len());
+    for (month, records) in partitions {
+        let partition = dir
+            .as_ref()
+            .join(format!("month={month}"))
+            .join(format!("force={force}"));
+        fs::create_dir_all(&partition)?;
+        let path = partition.join("part-0.parquet");
+        write_batch(File::create(&path)?, &batch(&records)?)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn batch<T: ToArrow>(records: &[&T]) -> Result<RecordBatch, ExportError> {
+    Ok(RecordBatch::try_new(T::schema(), T::columns(records))?)
+}
+
+fn write_batch(writer: impl Write + Send, batch: &RecordBatch) -> Result<(), ExportError> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn schema(fields: Vec<Field>) -> SchemaRef {
+    let metadata = [(
+        "uk_police_api.schema_version".to_string(),
+        SCHEMA_VERSION.to_string(),
+    )];
+    Arc::new(Schema::new(fields).with_metadata(metadata.into()))
+}
+
+fn utf8(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Utf8, nullable)
+}
+
+/// Columns describing a record's location, shared by several schemas.
+fn location_fields() -> [Field; 4] {
+    [
+        Field::new("latitude", DataType::Float64, true),
+        Field::new("longitude", DataType::Float64, true),
+        Field::new("street_id", DataType::UInt64, true),
+        utf8("street_name", true),
+    ]
+}
+
+fn strings<'a, T>(records: &[&'a T], value: impl Fn(&'a T) -> Option<&'a str>) -> ArrayRef {
+    Arc::new(
+        records
+            .iter()
+            .map(|record| value(record))
+            .collect::<StringArray>(),
+    )
+}
+
+fn bools<T>(records: &[&T], value: impl Fn(&T) -> Option<bool>) -> ArrayRef {
+    Arc::new(
+        records
+            .iter()
+            .map(|record| value(record))
+            .collect::<BooleanArray>(),
+    )
+}
+
+fn location_columns<'a, T>(
+    records: &[&'a T],
+    location: impl Fn(&'a T) -> Option<&'a Location>,
+) -> [ArrayRef; 4] {
+    let coordinate = |value: &str| value.parse::<f64>().ok();
+    [
+        Arc::new(
+            records
+                .iter()
+                .map(|record| location(record).and_then(|at| coordinate(&at.latitude)))
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(
+            records
+                .iter()
+                .map(|record| location(record).and_then(|at| coordinate(&at.longitude)))
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(
+            records
+                .iter()
+                .map(|record| location(record).map(|at| at.street.id.0))
+                .collect::<UInt64Array>(),
+        ),
+        strings(records, |record| {
+            location(record).map(|at| at.street.name.as_str())
+        }),
+    ]
+}
+
+static CRIME_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let mut fields = vec![
+        Field::new("id", DataType::UInt64, false),
+        utf8("persistent_id", true),
+        utf8("category", false),
+        utf8("month", false),
+        utf8("location_type", true),
+        utf8("location_subtype", false),
+        utf8("context", false),
+    ];
+    fields.extend(location_fields());
+    fields.extend([utf8("outcome_category", true), utf8("outcome_date", true)]);
+    schema(fields)
+});
+
+impl ToArrow for Crime {
+    fn schema() -> SchemaRef {
+        CRIME_SCHEMA.clone()
+    }
+
+    fn columns(records: &[&Self]) -> Vec<ArrayRef> {
+        let mut columns = vec![
+            Arc::new(
+                records
+                    .iter()
+                    .map(|crime| crime.id)
+                    .collect::<UInt64Array>(),
+            ) as ArrayRef,
+            strings(records, |crime| {
+                crime.persistent_id.as_ref().map(|id| id.as_str())
+            }),
+            strings(records, |crime| Some(&crime.category)),
+            strings(records, |crime| Some(&crime.month)),
+            strings(records, |crime| crime.location_type.as_deref()),
+            strings(records, |crime| Some(&crime.location_subtype)),
+            strings(records, |crime| Some(&crime.context)),
+        ];
+        columns.extend(location_columns(records, |crime| crime.location.as_ref()));
+        columns.extend([
+            strings(records, |crime| {
+                crime
+                    .outcome_status
+                    .as_ref()
+                    .map(|status| status.category.as_str())
+            }),
+            strings(records, |crime| {
+                crime
+                    .outcome_status
+                    .as_ref()
+                    .map(|status| status.date.as_str())
+            }),
+        ]);
+        columns
+    }
+
+    fn month(&self) -> Option<&str> {
+        Some(&self.month)
+    }
+}
+
+static OUTCOME_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let mut fields = vec![
+        utf8("category", false),
+        utf8("category_name", false),
+        utf8("date", false),
+        utf8("person_id", true),
+        Field::new("crime_id", DataType::UInt64, false),
+        utf8("crime_persistent_id", true),
+        utf8("crime_category", false),
+        utf8("crime_month", false),
+    ];
+    fields.extend(location_fields());
+    schema(fields)
+});
+
+impl ToArrow for Outcome {
+    fn schema() -> SchemaRef {
+        OUTCOME_SCHEMA.clone()
+    }
+
+    fn columns(records: &[&Self]) -> Vec<ArrayRef> {
+        let mut columns = vec![
+            strings(records, |outcome| Some(outcome.category.code.as_str())),
+            strings(records, |outcome| Some(&outcome.category.name)),
+            strings(records, |outcome| Some(&outcome.date)),
+            strings(records, |outcome| outcome.person_id.as_deref()),
+            Arc::new(
+                records
+                    .iter()
+                    .map(|outcome| outcome.crime.id)
+                    .collect::<UInt64Array>(),
+            ),
+            strings(records, |outcome| {
+                outcome.crime.persistent_id.as_ref().map(|id| id.as_str())
+            }),
+            strings(records, |outcome| Some(&outcome.crime.category)),
+            strings(records, |outcome| Some(&outcome.crime.month)),
+        ];
+        columns.extend(location_columns(records, |outcome| {
+            outcome.crime.location.as_ref()
+        }));
+        columns
+    }
+
+    fn month(&self) -> Option<&str> {
+        Some(&self.date)
+    }
+}
+
+static STOP_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let mut fields = vec![
+        utf8("type", true),
+        Field::new("involved_person", DataType::Boolean, true),
+        utf8("datetime", true),
+        utf8("month", true),
+        Field::new("operation", DataType::Boolean, true),
+        utf8("operation_name", true),
+    ];
+    fields.extend(location_fields());
+    fields.extend([
+        utf8("gender", true),
+        utf8("age_range", true),
+        utf8("self_defined_ethnicity", true),
+        utf8("officer_defined_ethnicity", true),
+        utf8("legislation", true),
+        utf8("object_of_search", true),
+        utf8("outcome", true),
+        Field::new(
+            "outcome_linked_to_object_of_search",
+            DataType::Boolean,
+            true,
+        ),
+        Field::new(
+            "removal_of_more_than_outer_clothing",
+            DataType::Boolean,
+            true,
+        ),
+        utf8("outcome_object_id", true),
+        utf8("outcome_object_name", true),
+    ]);
+    schema(fields)
+});
+
+impl ToArrow for StopAndSearch {
+    fn schema() -> SchemaRef {
+        STOP_SCHEMA.clone()
+    }
+
+    fn columns(records: &[&Self]) -> Vec<ArrayRef> {
+        let mut columns = vec![
+            strings(records, |stop| stop.kind.as_ref().map(|kind| kind.as_str())),
+            bools(records, |stop| stop.involved_person),
+            strings(records, |stop| stop.datetime.as_deref()),
+            strings(records, ToArrow::month),
+            bools(records, |stop| stop.operation),
+            strings(records, |stop| stop.operation_name.as_deref()),
+        ];
+        columns.extend(location_columns(records, |stop| stop.location.as_ref()));
+        columns.extend([
+            strings(records, |stop| {
+                stop.gender.as_ref().map(|value| value.as_str())
+            }),
+            strings(records, |stop| {
+                stop.age_range.as_ref().map(|value| value.as_str())
+            }),
+            strings(records, |stop| {
+                stop.self_defined_ethnicity
+                    .as_ref()
+                    .map(|value| value.as_str())
+            }),
+            strings(records, |stop| {
+                stop.officer_defined_ethnicity
+                    .as_ref()
+                    .map(|value| value.as_str())
+            }),
+            strings(records, |stop| {
+                stop.legislation.as_ref().map(|value| value.as_str())
+            }),
+            strings(records, |stop| {
+                stop.object_of_search.as_ref().map(|value| value.as_str())
+            }),
+            strings(records, |stop| stop.outcome.as_deref()),
+            bools(records, |stop| stop.outcome_linked_to_object_of_search),
+            bools(records, |stop| stop.removal_of_more_than_outer_clothing),
+            strings(records, |stop| {
+                stop.outcome_object
+                    .as_ref()
+                    .and_then(|object| object.id.as_deref())
+            }),
+            strings(records, |stop| {
+                stop.outcome_object
+                    .as_ref()
+                    .and_then(|object| object.name.as_deref())
+            }),
+        ]);
+        columns
+    }
+
+    /// The month of the stop's `datetime`.
+    fn month(&self) -> Option<&str> {
+        self.datetime
+            .as_deref()
+            .and_then(|datetime| datetime.get(..7))
+    }
+}
+
+static ID_NAME_SCHEMA: LazyLock<SchemaRef> =
+    LazyLock::new(|| schema(vec![utf8("id", false), utf8("name", false)]));
+
+impl ToArrow for Force {
+    fn schema() -> SchemaRef {
+        ID_NAME_SCHEMA.clone()
+    }
+
+    fn columns(records: &[&Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(records, |force| Some(force.id.as_str())),
+            strings(records, |force| Some(&force.name)),
+        ]
+    }
+}
+
+impl ToArrow for Neighbourhood {
+    fn schema() -> SchemaRef {
+        ID_NAME_SCHEMA.clone()
+    }
+
+    fn columns(records: &[&Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(records, |neighbourhood| Some(neighbourhood.id.as_str())),
+            strings(records, |neighbourhood| Some(&neighbourhood.name)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+
+    use super::*;
+
+    fn crime(id: u64, month: &str, location: bool) -> Crime {
+        let location = location.then(|| {
+            json!({
+                "latitude": "52.6297",
+                "street": { "id": 100, "name": "On or near High Street" },
+                "longitude": "-1.1316"
+            })
+        });
+        serde_json::from_value(json!({
+            "category": "burglary",
+            "persistent_id": "",
+            "location_subtype": "",
+            "id": id,
+            "location": location,
+            "context": "",
+            "month": month,
+            "location_type": "Force",
+            "outcome_status": { "category": "Under investigation", "date": month }
+        }))
+        .unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("uk-police-api-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_crime_batch_flattens_nested_fields() {
+        let batch = record_batch(&[crime(1, "2024-01", true), crime(2, "2024-01", false)]).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema(), Crime::schema());
+        assert_eq!(
+            batch.schema().metadata()["uk_police_api.schema_version"],
+            SCHEMA_VERSION
+        );
+
+        let latitude = batch
+            .column_by_name("latitude")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(latitude.value(0), 52.6297);
+        assert!(latitude.is_null(1));
+
+        let outcome = batch
+            .column_by_name("outcome_category")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(outcome.value(0), "under-investigation");
+        let persistent_id = batch.column_by_name("persistent_id").unwrap();
+        assert_eq!(persistent_id.null_count(), 2);
+    }
+
+    #[test]
+    fn test_stop_batch_matches_schema() {
+        let stop: StopAndSearch = serde_json::from_value(json!({
+            "type": "Person search",
+            "involved_person": true,
+            "datetime": "2024-01-15T12:30:00+00:00",
+            "gender": "Male",
+            "outcome_object": { "id": "bu-no-further-action", "name": "A no further action disposal" }
+        }))
+        .unwrap();
+        let batch = record_batch(&[stop]).unwrap();
+        assert_eq!(batch.num_columns(), StopAndSearch::schema().fields().len());
+        let month = batch
+            .column_by_name("month")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(month.value(0), "2024-01");
+    }
+
+    #[test]
+    fn test_write_partitioned_round_trips() {
+        let dir = temp_dir("partitioned");
+        let force: ForceId = "leicestershire".parse().unwrap();
+        let crimes = [
+            crime(1, "2024-01", true),
+            crime(2, "2024-02", true),
+            crime(3, "2024-01", false),
+        ];
+        let paths = write_partitioned(&dir, &force, &crimes).unwrap();
+        assert_eq!(
+            paths,
+            [
+                dir.join("month=2024-01/force=leicestershire/part-0.parquet"),
+                dir.join("month=2024-02/force=leicestershire/part-0.parquet"),
+            ]
+        );
+
+        // Exporting again replaces the partition rather than appending.
+        write_partitioned(&dir, &force, &crimes).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&paths[0]).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+        let ids = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(ids.values(), &[1, 3]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
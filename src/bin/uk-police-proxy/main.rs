@@ -0,0 +1,256 @@
+//! `uk-police-proxy`: a local HTTP server exposing the same `/api/...` paths
+//! as data.police.uk, so several services can share one cache and one rate
+//! limit.
+//!
+//! Built with the `proxy` feature:
+//!
+//! ```sh
+//! cargo install uk-police-api --features proxy
+//! uk-police-proxy --listen 127.0.0.1:8080 --cache-ttl 900
+//! ```
+//!
+//! Point clients at `http://127.0.0.1:8080/api`, e.g. with
+//! `Client::new().with_base_url("http://127.0.0.1:8080/api")`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::{HeaderValue, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use clap::Parser;
+use serde_json::json;
+use uk_police_api::middleware::{Cache, RateLimit, Retry};
+use uk_police_api::{Client, Error};
+
+#[derive(Parser)]
+#[command(
+    name = "uk-police-proxy",
+    version,
+    about = "Caching proxy for the UK Police API"
+)]
+struct Args {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    /// Base URL of the upstream API.
+    #[arg(long, default_value = "https://data.police.uk/api")]
+    upstream: String,
+    /// Seconds to cache successful responses for.
+    #[arg(long, default_value_t = 900)]
+    cache_ttl: u64,
+    /// Maximum number of cached responses.
+    #[arg(long, default_value_t = 4096)]
+    cache_capacity: usize,
+    /// Upstream requests per second, shared by every caller.
+    #[arg(long, default_value_t = 15)]
+    rate: u32,
+    /// Upstream burst size.
+    #[arg(long, default_value_t = 30)]
+    burst: u32,
+    /// Retries for transient upstream failures.
+    #[arg(long, default_value_t = 3)]
+    retries: u32,
+}
+
+/// State shared by every request handler.
+#[derive(Clone)]
+struct Proxy {
+    client: Client,
+    cache: Cache,
+    counters: Arc<Counters>,
+}
+
+#[derive(Default)]
+struct Counters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Proxy {
+    fn new(args: &Args) -> Self {
+        let cache = Cache::with_capacity(Duration::from_secs(args.cache_ttl), args.cache_capacity);
+        // Cache outermost so hits skip the rate limit; the limit applies to
+        // every retry attempt.
+        let client = Client::new()
+            .with_base_url(&args.upstream)
+            .with_middleware(cache.clone())
+            .with_middleware(Retry::new(args.retries))
+            .with_middleware(RateLimit::new(args.rate, args.burst));
+        Self {
+            client,
+            cache,
+            counters: Arc::default(),
+        }
+    }
+}
+
+fn router(proxy: Proxy) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/stats", get(stats))
+        .fallback(relay)
+        .with_state(proxy)
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn stats(State(proxy): State<Proxy>) -> Json<serde_json::Value> {
+    let cache = proxy.cache.stats();
+    Json(json!({
+        "requests": proxy.counters.requests.load(Ordering::Relaxed),
+        "errors": proxy.counters.errors.load(Ordering::Relaxed),
+        "cache": {
+            "hits": cache.hits,
+            "misses": cache.misses,
+            "entries": cache.entries,
+        },
+    }))
+}
+
+/// Relays `GET /api/<path>` to the upstream API through the client.
+async fn relay(State(proxy): State<Proxy>, method: Method, uri: Uri) -> Response {
+    let Some(path) = uri.path().strip_prefix("/api/") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if method != Method::GET {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_string(),
+    };
+
+    proxy.counters.requests.fetch_add(1, Ordering::Relaxed);
+    match proxy.client.get_raw(&path_and_query).await {
+        Ok(response) => {
+            let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK);
+            let content_type = response
+                .headers
+                .get(header::CONTENT_TYPE)
+                .cloned()
+                .unwrap_or(HeaderValue::from_static("application/json"));
+            (
+                status,
+                [(header::CONTENT_TYPE, content_type)],
+                response.body,
+            )
+                .into_response()
+        }
+        Err(err) => {
+            proxy.counters.errors.fetch_add(1, Ordering::Relaxed);
+            match err {
+                Error::Api { status, body, .. } => {
+                    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+                    (status, body).into_response()
+                }
+                other => (
+                    StatusCode::BAD_GATEWAY,
+                    Json(json!({ "error": other.to_string() })),
+                )
+                    .into_response(),
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    eprintln!(
+        "uk-police-proxy listening on http://{}/api, relaying to {}",
+        listener.local_addr()?,
+        args.upstream
+    );
+    axum::serve(listener, router(Proxy::new(&args)))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn start(upstream: &str) -> SocketAddr {
+        let args = Args::parse_from(["uk-police-proxy", "--upstream", upstream]);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(axum::serve(listener, router(Proxy::new(&args))).into_future());
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_relays_and_caches_responses() {
+        let upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": "leicestershire", "name": "Leicestershire Police" }
+            ])))
+            .expect(1)
+            .mount(&upstream)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/forces/atlantis"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&upstream)
+            .await;
+
+        let addr = start(&upstream.uri()).await;
+        let client = Client::new().with_base_url(format!("http://{addr}/api"));
+        for _ in 0..2 {
+            let forces = client.forces().await.unwrap();
+            assert_eq!(forces[0].name, "Leicestershire Police");
+        }
+        let err = client
+            .force(&"atlantis".parse().unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Api { status: 404, .. }));
+
+        let http = reqwest::Client::new();
+        let health: serde_json::Value = http
+            .get(format!("http://{addr}/health"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(health["status"], "ok");
+
+        let stats: serde_json::Value = http
+            .get(format!("http://{addr}/stats"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats["requests"], 3);
+        assert_eq!(stats["errors"], 1);
+        assert_eq!(stats["cache"]["hits"], 1);
+        assert_eq!(stats["cache"]["misses"], 2);
+        assert_eq!(stats["cache"]["entries"], 1);
+
+        let status = http
+            .get(format!("http://{addr}/elsewhere"))
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, 404);
+    }
+}
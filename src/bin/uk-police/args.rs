@@ -0,0 +1,104 @@
+//! Parsers for command-line values.
+
+use uk_police_api::Coordinate;
+
+/// Parses a `lat,lng` pair.
+pub fn parse_point(value: &str) -> Result<Coordinate, String> {
+    let (lat, lng) = value
+        .split_once(',')
+        .ok_or_else(|| format!("expected `lat,lng`, got {value:?}"))?;
+    let parse = |part: &str| {
+        part.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid coordinate {part:?} in {value:?}"))
+    };
+    Ok(Coordinate {
+        lat: parse(lat)?,
+        lng: parse(lng)?,
+    })
+}
+
+/// Parses a polygon as `lat,lng:lat,lng:...`, the API's own format.
+pub fn parse_polygon(value: &str) -> Result<Vec<Coordinate>, String> {
+    let points = value
+        .split(':')
+        .map(parse_point)
+        .collect::<Result<Vec<_>, _>>()?;
+    if points.len() < 3 {
+        return Err(format!(
+            "a polygon needs at least 3 points, got {}",
+            points.len()
+        ));
+    }
+    Ok(points)
+}
+
+/// A month (`YYYY-MM`) or inclusive range of months (`YYYY-MM..YYYY-MM`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthRange {
+    start: i32,
+    end: i32,
+}
+
+impl MonthRange {
+    /// Returns every month in the range as `YYYY-MM`.
+    pub fn months(&self) -> Vec<String> {
+        (self.start..=self.end)
+            .map(|index| format!("{:04}-{:02}", index / 12, index % 12 + 1))
+            .collect()
+    }
+}
+
+/// Parses a [`MonthRange`].
+pub fn parse_month_range(value: &str) -> Result<MonthRange, String> {
+    let (start, end) = value.split_once("..").unwrap_or((value, value));
+    let start = month_index(start)?;
+    let end = month_index(end)?;
+    if start > end {
+        return Err(format!("range {value:?} ends before it starts"));
+    }
+    Ok(MonthRange { start, end })
+}
+
+fn month_index(value: &str) -> Result<i32, String> {
+    let invalid = || format!("expected a month as `YYYY-MM`, got {value:?}");
+    let (year, month) = value.split_once('-').ok_or_else(invalid)?;
+    if year.len() != 4 || month.len() != 2 {
+        return Err(invalid());
+    }
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month: i32 = month.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) {
+        return Err(invalid());
+    }
+    Ok(year * 12 + month - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_point_and_polygon() {
+        let point = parse_point("52.63, -1.13").unwrap();
+        assert_eq!((point.lat, point.lng), (52.63, -1.13));
+        assert!(parse_point("52.63").is_err());
+        assert!(parse_point("north,south").is_err());
+
+        let polygon = parse_polygon("52.268,0.543:52.794,0.238:52.130,0.478").unwrap();
+        assert_eq!(polygon.len(), 3);
+        assert!(parse_polygon("52.268,0.543:52.794,0.238").is_err());
+    }
+
+    #[test]
+    fn test_parse_month_range() {
+        assert_eq!(parse_month_range("2024-01").unwrap().months(), ["2024-01"]);
+        assert_eq!(
+            parse_month_range("2023-11..2024-02").unwrap().months(),
+            ["2023-11", "2023-12", "2024-01", "2024-02"]
+        );
+        assert!(parse_month_range("2024-03..2024-01").is_err());
+        assert!(parse_month_range("2024-13").is_err());
+        assert!(parse_month_range("24-01").is_err());
+    }
+}
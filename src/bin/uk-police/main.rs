@@ -0,0 +1,397 @@
+//! `uk-police`: query the UK Police API from the command line.
+//!
+//! Built with the `cli` feature:
+//!
+//! ```sh
+//! cargo install uk-police-api --features cli
+//! uk-police crimes street --point 52.6297,-1.1316 --date 2024-01..2024-03 --format csv
+//! ```
+
+mod args;
+mod output;
+
+use std::future::Future;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
+use serde::Serialize;
+use serde_json::Value;
+use uk_police_api::{
+    Area, Client, Coordinate, ForceId, LocationId, NeighbourhoodKey, PersistentId,
+};
+
+use crate::args::{MonthRange, parse_month_range, parse_point, parse_polygon};
+use crate::output::{Format, Geometry};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Parser)]
+#[command(name = "uk-police", version, about = "Query the UK Police API")]
+struct Cli {
+    /// Output format.
+    #[arg(long, short, value_enum, default_value_t = Format::Json, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List all police forces.
+    Forces,
+    /// Show details for a force.
+    Force {
+        /// Force ID (e.g. "leicestershire").
+        id: ForceId,
+    },
+    /// Query crimes.
+    #[command(subcommand)]
+    Crimes(CrimesCommand),
+    /// Street-level outcomes in an area, or every outcome for one crime.
+    Outcomes {
+        #[command(flatten)]
+        area: AreaArgs,
+        /// Persistent ID of a crime; lists its outcomes instead of an area's.
+        #[arg(long, conflicts_with_all = ["point", "poly", "location_id", "date"])]
+        crime: Option<PersistentId>,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+    /// Query neighbourhoods.
+    #[command(subcommand)]
+    Neighbourhood(NeighbourhoodCommand),
+    /// Query stop and searches.
+    #[command(subcommand)]
+    Stops(StopsCommand),
+}
+
+#[derive(Subcommand)]
+enum CrimesCommand {
+    /// Street-level crimes in an area.
+    Street {
+        #[command(flatten)]
+        area: AreaArgs,
+        #[command(flatten)]
+        category: CategoryArgs,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+    /// Crimes at a location.
+    AtLocation {
+        /// Location ID (a street's `id`).
+        location_id: u64,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+    /// Crimes a force could not map to a location.
+    NoLocation {
+        /// Force ID (e.g. "leicestershire").
+        #[arg(long)]
+        force: ForceId,
+        #[command(flatten)]
+        category: CategoryArgs,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum NeighbourhoodCommand {
+    /// Find the neighbourhood responsible for a point.
+    Locate {
+        /// Point as `lat,lng`.
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        point: Coordinate,
+    },
+    /// Show details for a neighbourhood.
+    Detail(KeyArgs),
+    /// Show the boundary of a neighbourhood.
+    Boundary(KeyArgs),
+    /// List a neighbourhood's policing team.
+    Team(KeyArgs),
+    /// List a neighbourhood's events.
+    Events(KeyArgs),
+    /// List a neighbourhood's policing priorities.
+    Priorities(KeyArgs),
+}
+
+#[derive(Subcommand)]
+enum StopsCommand {
+    /// Stop and searches in an area.
+    Street {
+        #[command(flatten)]
+        area: AreaArgs,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+    /// Stop and searches reported by a force.
+    Force {
+        /// Force ID (e.g. "leicestershire").
+        force: ForceId,
+        #[command(flatten)]
+        date: DateArgs,
+    },
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct AreaArgs {
+    /// Search within a mile of a point, given as `lat,lng`.
+    #[arg(long, value_parser = parse_point, allow_hyphen_values = true)]
+    point: Option<Coordinate>,
+    /// Search within a polygon, given as `lat,lng:lat,lng:...`.
+    #[arg(long, value_parser = |value: &str| parse_polygon(value).map(Polygon), allow_hyphen_values = true)]
+    poly: Option<Polygon>,
+    /// Search at a location ID (a street's `id`).
+    #[arg(long)]
+    location_id: Option<u64>,
+}
+
+/// Wraps polygon points so clap treats them as a single value.
+#[derive(Clone)]
+struct Polygon(Vec<Coordinate>);
+
+impl AreaArgs {
+    fn area(&self) -> Option<Area> {
+        if let Some(point) = &self.point {
+            Some(Area::Point(point.clone()))
+        } else if let Some(Polygon(points)) = &self.poly {
+            Some(Area::Custom(points.clone()))
+        } else {
+            self.location_id.map(|id| Area::LocationId(LocationId(id)))
+        }
+    }
+
+    fn require(&self) -> Area {
+        self.area().unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "one of --point, --poly or --location-id is required",
+                )
+                .exit()
+        })
+    }
+}
+
+#[derive(Args)]
+struct CategoryArgs {
+    /// Crime category slug (see `crime_categories`), e.g. "burglary".
+    #[arg(long, default_value = "all-crime")]
+    category: String,
+}
+
+#[derive(Args)]
+struct DateArgs {
+    /// Month (`YYYY-MM`) or inclusive range (`YYYY-MM..YYYY-MM`). Defaults to
+    /// the latest month available.
+    #[arg(long, value_parser = parse_month_range)]
+    date: Option<MonthRange>,
+}
+
+#[derive(Args)]
+struct KeyArgs {
+    /// Neighbourhood as `force/neighbourhood` (e.g. "leicestershire/NC04").
+    key: NeighbourhoodKey,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: &Cli) -> Result<(), BoxError> {
+    let client = Client::new();
+    let (value, geometry) = fetch(&client, &cli.command).await?;
+    let mut out = BufWriter::new(io::stdout().lock());
+    output::write(&mut out, cli.format, geometry, &value)?;
+    out.flush()?;
+    Ok(())
+}
+
+async fn fetch(client: &Client, command: &Command) -> Result<(Value, Geometry), BoxError> {
+    let value = match command {
+        Command::Forces => to_value(client.forces().await?)?,
+        Command::Force { id } => to_value(client.force(id).await?)?,
+        Command::Crimes(CrimesCommand::Street {
+            area,
+            category,
+            date,
+        }) => {
+            let area = &area.require();
+            each_month(date, |month| async move {
+                client
+                    .street_level_crimes(&category.category, area, month.as_deref())
+                    .await
+            })
+            .await?
+        }
+        Command::Crimes(CrimesCommand::AtLocation { location_id, date }) => {
+            let location_id = LocationId(*location_id);
+            each_month(date, |month| async move {
+                client
+                    .crimes_at_location(location_id, month.as_deref())
+                    .await
+            })
+            .await?
+        }
+        Command::Crimes(CrimesCommand::NoLocation {
+            force,
+            category,
+            date,
+        }) => {
+            each_month(date, |month| async move {
+                client
+                    .crimes_no_location(&category.category, force, month.as_deref())
+                    .await
+            })
+            .await?
+        }
+        Command::Outcomes {
+            crime: Some(crime), ..
+        } => to_value(client.outcomes_for_crime(crime).await?)?,
+        Command::Outcomes { area, date, .. } => {
+            let area = &area.require();
+            each_month(date, |month| async move {
+                client.street_level_outcomes(area, month.as_deref()).await
+            })
+            .await?
+        }
+        Command::Neighbourhood(command) => return fetch_neighbourhood(client, command).await,
+        Command::Stops(StopsCommand::Street { area, date }) => match area.require() {
+            Area::LocationId(location_id) => {
+                each_month(date, |month| async move {
+                    client
+                        .stops_at_location(location_id, month.as_deref())
+                        .await
+                })
+                .await?
+            }
+            area => {
+                let area = &area;
+                each_month(date, |month| async move {
+                    client.stops_street(area, month.as_deref()).await
+                })
+                .await?
+            }
+        },
+        Command::Stops(StopsCommand::Force { force, date }) => {
+            each_month(date, |month| async move {
+                client.stops_force(force, month.as_deref()).await
+            })
+            .await?
+        }
+    };
+    Ok((value, Geometry::Points))
+}
+
+async fn fetch_neighbourhood(
+    client: &Client,
+    command: &NeighbourhoodCommand,
+) -> Result<(Value, Geometry), BoxError> {
+    let value = match command {
+        NeighbourhoodCommand::Locate { point } => {
+            to_value(client.locate_neighbourhood(point.lat, point.lng).await?)?
+        }
+        NeighbourhoodCommand::Detail(KeyArgs { key }) => {
+            to_value(client.neighbourhood(key).await?)?
+        }
+        NeighbourhoodCommand::Boundary(KeyArgs { key }) => {
+            let boundary = to_value(client.neighbourhood_boundary(key).await?)?;
+            return Ok((boundary, Geometry::Polygon));
+        }
+        NeighbourhoodCommand::Team(KeyArgs { key }) => {
+            to_value(client.neighbourhood_team(key).await?)?
+        }
+        NeighbourhoodCommand::Events(KeyArgs { key }) => {
+            to_value(client.neighbourhood_events(key).await?)?
+        }
+        NeighbourhoodCommand::Priorities(KeyArgs { key }) => {
+            to_value(client.neighbourhood_priorities(key).await?)?
+        }
+    };
+    Ok((value, Geometry::Points))
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, BoxError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Runs `fetch` for each month in `date` (or once for the latest month) and
+/// concatenates the results.
+async fn each_month<T, F, Fut>(date: &DateArgs, mut fetch: F) -> Result<Value, BoxError>
+where
+    T: Serialize,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, uk_police_api::Error>>,
+{
+    let months = match &date.date {
+        Some(range) => range.months().into_iter().map(Some).collect(),
+        None => vec![None],
+    };
+    let mut records = Vec::new();
+    for month in months {
+        records.extend(fetch(month).await?);
+    }
+    to_value(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parses_area_and_date_range() {
+        let cli = Cli::try_parse_from([
+            "uk-police",
+            "crimes",
+            "street",
+            "--poly",
+            "52.268,0.543:52.794,0.238:52.130,0.478",
+            "--date",
+            "2024-01..2024-03",
+            "--format",
+            "csv",
+        ])
+        .unwrap();
+        assert_eq!(cli.format, Format::Csv);
+        let Command::Crimes(CrimesCommand::Street { area, date, .. }) = cli.command else {
+            panic!("expected crimes street");
+        };
+        assert!(matches!(area.area(), Some(Area::Custom(points)) if points.len() == 3));
+        assert_eq!(date.date.unwrap().months().len(), 3);
+
+        assert!(
+            Cli::try_parse_from([
+                "uk-police",
+                "stops",
+                "street",
+                "--point",
+                "1,2",
+                "--location-id",
+                "3"
+            ])
+            .is_err()
+        );
+        let crime = "a".repeat(64);
+        assert!(
+            Cli::try_parse_from(["uk-police", "outcomes", "--crime", &crime, "--point", "1,2"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["uk-police", "outcomes", "--crime", &crime]).is_ok());
+    }
+}
@@ -0,0 +1,309 @@
+//! Rendering of API responses as JSON, CSV, GeoJSON or a text table.
+
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde_json::{Map, Value, json};
+
+/// Output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Pretty-printed JSON, as returned by the API.
+    Json,
+    /// One row per record, with nested fields as dotted columns.
+    Csv,
+    /// A GeoJSON feature collection of the records that have a location.
+    Geojson,
+    /// An aligned text table.
+    Table,
+}
+
+/// How records map to GeoJSON geometries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Geometry {
+    /// Each record is a feature, placed at its own coordinates.
+    Points,
+    /// The records are the vertices of a single polygon (a boundary).
+    Polygon,
+}
+
+/// Widest a table cell is allowed to be before it is truncated.
+const MAX_CELL_WIDTH: usize = 40;
+
+/// Writes `value` (a record or array of records) in `format`.
+pub fn write(
+    out: &mut impl Write,
+    format: Format,
+    geometry: Geometry,
+    value: &Value,
+) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, value)?;
+            writeln!(out)
+        }
+        Format::Csv => write_csv(out, value),
+        Format::Geojson => {
+            serde_json::to_writer_pretty(&mut *out, &geojson(value, geometry))?;
+            writeln!(out)
+        }
+        Format::Table => write_table(out, value),
+    }
+}
+
+fn records(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    }
+}
+
+/// Flattens a record into `(column, cell)` pairs. Nested objects become
+/// dotted columns; arrays are kept as compact JSON.
+fn flatten(prefix: &str, value: &Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&column, field, cells);
+            }
+        }
+        Value::Null => cells.push((prefix.to_string(), String::new())),
+        Value::String(text) => cells.push((prefix.to_string(), text.clone())),
+        other => cells.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+/// Flattens every record and returns the union of their columns, in the
+/// order first seen, with one row of cells per record.
+fn rows(value: &Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let flattened: Vec<Vec<(String, String)>> = records(value)
+        .into_iter()
+        .map(|record| {
+            let mut cells = Vec::new();
+            let prefix = if record.is_object() { "" } else { "value" };
+            flatten(prefix, record, &mut cells);
+            cells
+        })
+        .collect();
+
+    let mut columns: Vec<String> = Vec::new();
+    for (column, _) in flattened.iter().flatten() {
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+    let rows = flattened
+        .into_iter()
+        .map(|cells| {
+            columns
+                .iter()
+                .map(|column| {
+                    cells
+                        .iter()
+                        .find(|(name, _)| name == column)
+                        .map(|(_, cell)| cell.clone())
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn write_csv(out: &mut impl Write, value: &Value) -> io::Result<()> {
+    let (columns, rows) = rows(value);
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(&columns)?;
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+    writer.flush()
+}
+
+fn write_table(out: &mut impl Write, value: &Value) -> io::Result<()> {
+    let (columns, rows) = rows(value);
+    let truncate = |cell: &str| {
+        let cell = cell.replace(['\n', '\r', '\t'], " ");
+        if cell.chars().count() > MAX_CELL_WIDTH {
+            let mut short: String = cell.chars().take(MAX_CELL_WIDTH - 1).collect();
+            short.push('…');
+            short
+        } else {
+            cell
+        }
+    };
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| truncate(cell)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+    writeln!(out, "{}", line(&columns))?;
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    writeln!(out, "{}", line(&rule))?;
+    for row in &rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    Ok(())
+}
+
+/// Reads a coordinate that the API may return as a string or a number.
+fn coordinate(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::String(text) => text.parse().ok(),
+        Value::Number(number) => number.as_f64(),
+        _ => None,
+    }
+}
+
+/// Finds a record's `[lng, lat]` position in the places the API puts one.
+fn position(record: &Value) -> Option<[f64; 2]> {
+    [record.get("location"), Some(record), record.get("centre")]
+        .into_iter()
+        .flatten()
+        .find_map(|place| {
+            Some([
+                coordinate(place.get("longitude"))?,
+                coordinate(place.get("latitude"))?,
+            ])
+        })
+}
+
+fn geojson(value: &Value, geometry: Geometry) -> Value {
+    let records = records(value);
+    let features: Vec<Value> = match geometry {
+        Geometry::Points => records
+            .into_iter()
+            .filter_map(|record| {
+                let position = position(record)?;
+                let properties = match record {
+                    Value::Object(fields) => fields.clone(),
+                    _ => Map::new(),
+                };
+                Some(json!({
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": position },
+                    "properties": properties,
+                }))
+            })
+            .collect(),
+        Geometry::Polygon => {
+            let mut ring: Vec<[f64; 2]> = records.into_iter().filter_map(position).collect();
+            if ring.first() != ring.last() {
+                ring.push(ring[0]);
+            }
+            if ring.len() < 4 {
+                Vec::new()
+            } else {
+                vec![json!({
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": [ring] },
+                    "properties": {},
+                })]
+            }
+        }
+    };
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: Format, geometry: Geometry, value: &Value) -> String {
+        let mut out = Vec::new();
+        write(&mut out, format, geometry, value).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn crimes() -> Value {
+        json!([
+            {
+                "category": "burglary",
+                "location": {
+                    "latitude": "52.6",
+                    "longitude": "-1.1",
+                    "street": { "id": 1, "name": "On or near High Street" }
+                },
+                "outcome_status": null
+            },
+            {
+                "category": "drugs",
+                "location": null,
+                "outcome_status": { "category": "Under investigation", "date": "2024-01" }
+            }
+        ])
+    }
+
+    #[test]
+    fn test_csv_flattens_nested_fields() {
+        let csv = render(Format::Csv, Geometry::Points, &crimes());
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "category,location.latitude,location.longitude,location.street.id,\
+             location.street.name,outcome_status,location,outcome_status.category,\
+             outcome_status.date"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "burglary,52.6,-1.1,1,On or near High Street,,,,"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "drugs,,,,,,,Under investigation,2024-01"
+        );
+    }
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let value = json!([{ "id": "kent", "name": "Kent Police" }, { "id": "met", "name": "x" }]);
+        assert_eq!(
+            render(Format::Table, Geometry::Points, &value),
+            "id    name\n----  -----------\nkent  Kent Police\nmet   x\n"
+        );
+    }
+
+    #[test]
+    fn test_geojson_points_and_polygon() {
+        let points = geojson(&crimes(), Geometry::Points);
+        assert_eq!(points["features"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            points["features"][0]["geometry"]["coordinates"],
+            json!([-1.1, 52.6])
+        );
+
+        let boundary = json!([
+            { "latitude": "52.0", "longitude": "-1.0" },
+            { "latitude": "52.1", "longitude": "-1.0" },
+            { "latitude": "52.1", "longitude": "-1.1" }
+        ]);
+        let polygon = geojson(&boundary, Geometry::Polygon);
+        let ring = &polygon["features"][0]["geometry"]["coordinates"][0];
+        assert_eq!(ring.as_array().unwrap().len(), 4);
+        assert_eq!(ring[0], ring[3]);
+    }
+}
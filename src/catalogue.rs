@@ -0,0 +1,494 @@
+//! An embedded catalogue of police forces.
+//!
+//! The list of forces changes rarely, so the catalogue answers most questions
+//! about a force without a request: its official name, where it operates and
+//! whether it publishes stop and search data. It is a snapshot taken at
+//! [`VERSION`]; use [`reconcile`] or
+//! [`Client::force_catalogue_drift`](crate::Client::force_catalogue_drift) to
+//! check it against the live API.
+//!
+//! # Example
+//!
+//! ```
+//! use uk_police_api::catalogue::{self, Nation};
+//!
+//! let met = catalogue::lookup("Met").unwrap();
+//! assert_eq!(met.id.as_str(), "metropolitan");
+//! assert_eq!(met.nation, Some(Nation::England));
+//!
+//! let welsh = catalogue::all()
+//!     .iter()
+//!     .filter(|force| force.nation == Some(Nation::Wales))
+//!     .count();
+//! assert_eq!(welsh, 4);
+//! ```
+
+use std::sync::LazyLock;
+
+use serde::Serialize;
+
+use crate::models::{Force, ForceId};
+
+/// Date the catalogue was last checked against the API, as `YYYY-MM`.
+pub const VERSION: &str = "2026-10";
+
+/// Nation of the United Kingdom a force operates in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Nation {
+    England,
+    Wales,
+    NorthernIreland,
+}
+
+/// Statistical region of England a force operates in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Region {
+    NorthEast,
+    NorthWest,
+    YorkshireAndTheHumber,
+    EastMidlands,
+    WestMidlands,
+    East,
+    London,
+    SouthEast,
+    SouthWest,
+}
+
+/// Catalogue entry for a police force.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ForceInfo {
+    /// Force identifier as used by the API.
+    pub id: ForceId,
+    /// Official name, as returned by [`Client::forces`](crate::Client::forces).
+    pub name: &'static str,
+    /// Nation the force operates in, or `None` for forces that operate
+    /// across nations (British Transport Police).
+    pub nation: Option<Nation>,
+    /// Region of England the force operates in, or `None` outside England.
+    pub region: Option<Region>,
+    /// Whether the force publishes stop and search data.
+    pub stop_and_search: bool,
+    /// Number of neighbourhoods the force reported at [`VERSION`], or `None`
+    /// if no count has been recorded. [`reconcile_neighbourhoods`] reports
+    /// counts that differ from the live API.
+    pub neighbourhoods: Option<u32>,
+    /// Other names the force is commonly known by (e.g. "Met", "GMP").
+    pub aliases: &'static [&'static str],
+}
+
+/// Builds a [`ForceInfo`]. The `@` form spells out every field; the short
+/// forms are for forces that publish stop and search data, and take an
+/// optional `neighbourhoods = N` count before any aliases.
+macro_rules! force {
+    (@ $id:literal, $name:literal, $nation:expr, $region:expr, $stops:expr, [$($count:literal)?], [$($alias:literal),*]) => {
+        ForceInfo {
+            id: ForceId::new_unchecked($id),
+            name: $name,
+            nation: $nation,
+            region: $region,
+            stop_and_search: $stops,
+            neighbourhoods: force!(@count $($count)?),
+            aliases: &[$($alias),*],
+        }
+    };
+    (@count) => {
+        None
+    };
+    (@count $count:literal) => {
+        Some($count)
+    };
+    ($id:literal, $name:literal, $nation:ident, $region:ident $(, neighbourhoods = $count:literal)? $(, aliases = [$($alias:literal),*])?) => {
+        force!(@ $id, $name, Some(Nation::$nation), Some(Region::$region), true, [$($count)?], [$($($alias),*)?])
+    };
+    ($id:literal, $name:literal, $nation:ident $(, neighbourhoods = $count:literal)? $(, aliases = [$($alias:literal),*])?) => {
+        force!(@ $id, $name, Some(Nation::$nation), None, true, [$($count)?], [$($($alias),*)?])
+    };
+}
+
+static FORCES: LazyLock<Vec<ForceInfo>> = LazyLock::new(|| {
+    vec![
+        force!(
+            "avon-and-somerset",
+            "Avon and Somerset Constabulary",
+            England,
+            SouthWest
+        ),
+        force!("bedfordshire", "Bedfordshire Police", England, East),
+        force!(@ "btp", "British Transport Police", None, None, true, [], []),
+        force!(
+            "cambridgeshire",
+            "Cambridgeshire Constabulary",
+            England,
+            East
+        ),
+        force!("cheshire", "Cheshire Constabulary", England, NorthWest),
+        force!(
+            "city-of-london",
+            "City of London Police",
+            England,
+            London,
+            aliases = ["CoLP"]
+        ),
+        force!("cleveland", "Cleveland Police", England, NorthEast),
+        force!("cumbria", "Cumbria Constabulary", England, NorthWest),
+        force!(
+            "derbyshire",
+            "Derbyshire Constabulary",
+            England,
+            EastMidlands
+        ),
+        force!(
+            "devon-and-cornwall",
+            "Devon & Cornwall Police",
+            England,
+            SouthWest
+        ),
+        force!("dorset", "Dorset Police", England, SouthWest),
+        force!("durham", "Durham Constabulary", England, NorthEast),
+        force!("dyfed-powys", "Dyfed-Powys Police", Wales),
+        force!("essex", "Essex Police", England, East),
+        force!(
+            "gloucestershire",
+            "Gloucestershire Constabulary",
+            England,
+            SouthWest
+        ),
+        force!(
+            "greater-manchester",
+            "Greater Manchester Police",
+            England,
+            NorthWest,
+            aliases = ["GMP"]
+        ),
+        force!("gwent", "Gwent Police", Wales),
+        force!(
+            "hampshire",
+            "Hampshire Constabulary",
+            England,
+            SouthEast,
+            aliases = ["Hampshire and Isle of Wight Constabulary", "HIOWC"]
+        ),
+        force!("hertfordshire", "Hertfordshire Constabulary", England, East),
+        force!(
+            "humberside",
+            "Humberside Police",
+            England,
+            YorkshireAndTheHumber
+        ),
+        force!("kent", "Kent Police", England, SouthEast),
+        force!("lancashire", "Lancashire Constabulary", England, NorthWest),
+        force!(
+            "leicestershire",
+            "Leicestershire Police",
+            England,
+            EastMidlands
+        ),
+        force!("lincolnshire", "Lincolnshire Police", England, EastMidlands),
+        force!("merseyside", "Merseyside Police", England, NorthWest),
+        force!(
+            "metropolitan",
+            "Metropolitan Police Service",
+            England,
+            London,
+            aliases = [
+                "Met",
+                "Met Police",
+                "Metropolitan Police",
+                "MPS",
+                "Scotland Yard"
+            ]
+        ),
+        force!("norfolk", "Norfolk Constabulary", England, East),
+        force!("north-wales", "North Wales Police", Wales),
+        force!(
+            "north-yorkshire",
+            "North Yorkshire Police",
+            England,
+            YorkshireAndTheHumber,
+            aliases = ["NYP"]
+        ),
+        force!(
+            "northamptonshire",
+            "Northamptonshire Police",
+            England,
+            EastMidlands
+        ),
+        force!(@ "northern-ireland", "Police Service of Northern Ireland", Some(Nation::NorthernIreland), None, false, [], ["PSNI"]),
+        force!("northumbria", "Northumbria Police", England, NorthEast),
+        force!(
+            "nottinghamshire",
+            "Nottinghamshire Police",
+            England,
+            EastMidlands
+        ),
+        force!("south-wales", "South Wales Police", Wales),
+        force!(
+            "south-yorkshire",
+            "South Yorkshire Police",
+            England,
+            YorkshireAndTheHumber,
+            aliases = ["SYP"]
+        ),
+        force!(
+            "staffordshire",
+            "Staffordshire Police",
+            England,
+            WestMidlands
+        ),
+        force!("suffolk", "Suffolk Constabulary", England, East),
+        force!("surrey", "Surrey Police", England, SouthEast),
+        force!("sussex", "Sussex Police", England, SouthEast),
+        force!(
+            "thames-valley",
+            "Thames Valley Police",
+            England,
+            SouthEast,
+            aliases = ["TVP"]
+        ),
+        force!("warwickshire", "Warwickshire Police", England, WestMidlands),
+        force!("west-mercia", "West Mercia Police", England, WestMidlands),
+        force!(
+            "west-midlands",
+            "West Midlands Police",
+            England,
+            WestMidlands,
+            aliases = ["WMP"]
+        ),
+        force!(
+            "west-yorkshire",
+            "West Yorkshire Police",
+            England,
+            YorkshireAndTheHumber,
+            aliases = ["WYP"]
+        ),
+        force!("wiltshire", "Wiltshire Police", England, SouthWest),
+    ]
+});
+
+/// Returns every force in the catalogue, sorted by ID.
+pub fn all() -> &'static [ForceInfo] {
+    &FORCES
+}
+
+/// Returns the catalogue entry for a force ID.
+pub fn get(id: &str) -> Option<&'static ForceInfo> {
+    FORCES
+        .binary_search_by(|force| force.id.as_str().cmp(id))
+        .ok()
+        .map(|index| &FORCES[index])
+}
+
+/// Finds a force by ID, official name or alias.
+///
+/// Matching ignores case, punctuation and a trailing "Police",
+/// "Constabulary" or "Police Service", and treats `&` as "and", so
+/// `"Avon & Somerset"`, `"avon-and-somerset"` and `"GMP"` all match.
+pub fn lookup(query: &str) -> Option<&'static ForceInfo> {
+    let query = normalise(query);
+    if query.is_empty() {
+        return None;
+    }
+    FORCES.iter().find(|force| {
+        [force.id.as_str(), force.name]
+            .iter()
+            .chain(force.aliases)
+            .any(|name| normalise(name) == query)
+    })
+}
+
+/// Lowercases, maps `&` to "and", collapses punctuation to single spaces and
+/// drops a trailing force-type suffix.
+fn normalise(name: &str) -> String {
+    let name = name.to_lowercase().replace('&', " and ");
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let end = match words.as_slice() {
+        [.., "police", "service"] if words.len() > 2 => words.len() - 2,
+        [.., "police" | "constabulary"] if words.len() > 1 => words.len() - 1,
+        _ => words.len(),
+    };
+    words[..end].join(" ")
+}
+
+/// Differences between the catalogue and a live list of forces.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CatalogueDrift {
+    /// Forces returned by the API that the catalogue does not know.
+    pub added: Vec<Force>,
+    /// Catalogue forces missing from the API response.
+    pub removed: Vec<&'static ForceInfo>,
+    /// Forces whose live name differs from the catalogue.
+    pub renamed: Vec<RenamedForce>,
+    /// Forces whose live neighbourhood count differs from the catalogue.
+    pub neighbourhoods: Vec<NeighbourhoodCountChange>,
+}
+
+impl CatalogueDrift {
+    /// Returns `true` if the catalogue matches the live list.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.neighbourhoods.is_empty()
+    }
+}
+
+/// A force whose live name differs from the catalogue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenamedForce {
+    /// Force identifier.
+    pub id: ForceId,
+    /// Name recorded in the catalogue.
+    pub catalogue_name: &'static str,
+    /// Name returned by the API.
+    pub live_name: String,
+}
+
+/// A force whose live neighbourhood count differs from the catalogue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NeighbourhoodCountChange {
+    /// Force identifier.
+    pub id: ForceId,
+    /// Count recorded in the catalogue, or `None` if none was recorded.
+    pub catalogue_count: Option<u32>,
+    /// Number of neighbourhoods returned by the API.
+    pub live_count: u32,
+}
+
+/// Compares the catalogue with forces returned by
+/// [`Client::forces`](crate::Client::forces).
+pub fn reconcile(live: &[Force]) -> CatalogueDrift {
+    let mut drift = CatalogueDrift::default();
+    for force in live {
+        match get(force.id.as_str()) {
+            Some(info) if info.name != force.name => drift.renamed.push(RenamedForce {
+                id: force.id.clone(),
+                catalogue_name: info.name,
+                live_name: force.name.clone(),
+            }),
+            Some(_) => {}
+            None => drift.added.push(force.clone()),
+        }
+    }
+    drift.removed = FORCES
+        .iter()
+        .filter(|info| !live.iter().any(|force| force.id == info.id))
+        .collect();
+    drift
+}
+
+/// Compares the catalogue's neighbourhood counts with live counts per
+/// force, as returned by
+/// [`Client::neighbourhoods`](crate::Client::neighbourhoods). Forces the
+/// catalogue does not know are skipped.
+pub fn reconcile_neighbourhoods<'a>(
+    live: impl IntoIterator<Item = (&'a ForceId, u32)>,
+) -> Vec<NeighbourhoodCountChange> {
+    live.into_iter()
+        .filter_map(|(id, live_count)| {
+            let info = get(id.as_str())?;
+            (info.neighbourhoods != Some(live_count)).then(|| NeighbourhoodCountChange {
+                id: id.clone(),
+                catalogue_count: info.neighbourhoods,
+                live_count,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn force(id: &str, name: &str) -> Force {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    #[test]
+    fn test_catalogue_is_sorted_and_valid() {
+        assert!(FORCES.windows(2).all(|pair| pair[0].id < pair[1].id));
+        for info in FORCES.iter() {
+            assert!(ForceId::new(info.id.as_str()).is_ok());
+            assert_eq!(info.region.is_some(), info.nation == Some(Nation::England));
+        }
+        assert_eq!(get("kent").unwrap().name, "Kent Police");
+        assert!(get("atlantis").is_none());
+    }
+
+    #[test]
+    fn test_lookup_by_name_or_alias() {
+        assert_eq!(lookup("Met").unwrap().id.as_str(), "metropolitan");
+        assert_eq!(
+            lookup("metropolitan police").unwrap().id.as_str(),
+            "metropolitan"
+        );
+        assert_eq!(lookup("GMP").unwrap().id.as_str(), "greater-manchester");
+        assert_eq!(
+            lookup("Avon & Somerset").unwrap().id.as_str(),
+            "avon-and-somerset"
+        );
+        assert_eq!(
+            lookup("Devon and Cornwall").unwrap().id.as_str(),
+            "devon-and-cornwall"
+        );
+        assert_eq!(
+            lookup("  Leicestershire Police ").unwrap().id.as_str(),
+            "leicestershire"
+        );
+        assert_eq!(
+            lookup("PSNI").unwrap().nation,
+            Some(Nation::NorthernIreland)
+        );
+        assert!(lookup("police").is_none());
+        assert!(lookup("").is_none());
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let mut live: Vec<Force> = FORCES
+            .iter()
+            .filter(|info| info.id.as_str() != "wiltshire")
+            .map(|info| force(info.id.as_str(), info.name))
+            .collect();
+        assert_eq!(reconcile(&live).removed.len(), 1);
+
+        live.push(force("wiltshire", "Wiltshire Police"));
+        assert!(reconcile(&live).is_empty());
+
+        live[0].name = "Avon and Somerset Police".to_string();
+        live.push(force("new-force", "New Force Police"));
+        let drift = reconcile(&live);
+        assert!(drift.removed.is_empty());
+        assert_eq!(drift.added[0].id.as_str(), "new-force");
+        assert_eq!(
+            drift.renamed[0].catalogue_name,
+            "Avon and Somerset Constabulary"
+        );
+        assert_eq!(drift.renamed[0].live_name, "Avon and Somerset Police");
+    }
+
+    #[test]
+    fn test_reconcile_neighbourhoods() {
+        let live: Vec<(ForceId, u32)> = FORCES
+            .iter()
+            .filter_map(|info| Some((info.id.clone(), info.neighbourhoods?)))
+            .collect();
+        assert!(reconcile_neighbourhoods(live.iter().map(|(id, count)| (id, *count))).is_empty());
+
+        let kent = get("kent").unwrap();
+        let unknown: ForceId = "new-force".parse().unwrap();
+        let changes = reconcile_neighbourhoods([(&kent.id, 1000), (&unknown, 3)]);
+        assert_eq!(
+            changes,
+            [NeighbourhoodCountChange {
+                id: kent.id.clone(),
+                catalogue_count: kent.neighbourhoods,
+                live_count: 1000,
+            }]
+        );
+    }
+}
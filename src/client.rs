@@ -1,9 +1,23 @@
+use std::sync::Arc;
+
+use crate::catalogue::{self, CatalogueDrift};
+#[cfg(feature = "extra-fields")]
+use crate::drift::{SchemaDrift, SchemaDriftReport};
 use crate::error::Error;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+use crate::middleware::{Middleware, Next};
 use crate::models::{
-    Area, Crime, CrimeCategory, CrimeLastUpdated, CrimeOutcomes, Force, ForceDetail, LatLng,
-    LocateNeighbourhoodResult, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent,
-    NeighbourhoodPriority, Outcome, SeniorOfficer, StopAndSearch,
+    Area, Coordinate, Crime, CrimeAvailability, CrimeCategory, CrimeLastUpdated, CrimeOutcomes,
+    Force, ForceDetail, ForceId, LatLng, LocateNeighbourhoodResult, LocationId, Neighbourhood,
+    NeighbourhoodDetail, NeighbourhoodEvent, NeighbourhoodKey, NeighbourhoodPriority, Outcome,
+    PersistentId, SeniorOfficer, StopAndSearch,
 };
+use crate::observe;
+use crate::request::{Decode, Request, Response};
+use crate::single_flight::SingleFlight;
+#[cfg(feature = "tracing")]
+use crate::trace;
 
 const BASE_URL: &str = "https://data.police.uk/api";
 
@@ -19,22 +33,71 @@ const BASE_URL: &str = "https://data.police.uk/api";
 /// # Ok(())
 /// # }
 /// ```
+///
+/// Concurrent identical requests made through a client or any of its clones
+/// share a single in-flight HTTP call. See [`Client::with_request_coalescing`].
 #[derive(Clone)]
 pub struct Client {
     http: reqwest::Client,
     base_url: String,
+    single_flight: Option<Arc<SingleFlight>>,
+    middleware: Arc<Vec<Arc<dyn Middleware>>>,
+    #[cfg(feature = "tracing")]
+    redact_sensitive_fields: bool,
+    #[cfg(feature = "extra-fields")]
+    schema_drift: Option<Arc<SchemaDrift>>,
 }
 
 impl Client {
-    async fn handle_response<T: serde::de::DeserializeOwned>(
-        response: reqwest::Response,
-    ) -> Result<T, Error> {
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Api { status, body });
+    /// Sends a request, returning whether the response came from another
+    /// caller's in-flight request.
+    async fn fetch(&self, request: Request) -> (Result<Response, Error>, bool) {
+        let (http, middleware) = (self.http.clone(), Arc::clone(&self.middleware));
+        let key = request.url.clone();
+        let endpoint = request.endpoint;
+        let fetch = async move { Next::new(&http, &middleware).run(request).await };
+        match &self.single_flight {
+            Some(single_flight) => {
+                let (result, coalesced) = single_flight.run(key, fetch).await;
+                observe::coalesced(endpoint, coalesced);
+                (result, coalesced)
+            }
+            None => (fetch.await, false),
         }
-        Ok(response.json().await?)
+    }
+
+    async fn get<T>(&self, request: Request) -> Result<T, Error>
+    where
+        T: Decode,
+    {
+        #[cfg(feature = "tracing")]
+        let span = trace::request_span(&request, self.redact_sensitive_fields);
+        #[cfg(any(feature = "metrics", feature = "extra-fields"))]
+        let endpoint = request.endpoint;
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let started = std::time::Instant::now();
+
+        let fetch = self.fetch(request);
+        #[cfg(feature = "tracing")]
+        let fetch = tracing::Instrument::instrument(fetch, span.clone());
+
+        #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+        let (result, coalesced) = fetch.await;
+        let result = result.and_then(|response| {
+            let value: T = serde_json::from_slice(&response.body)?;
+            Ok((response, value))
+        });
+
+        #[cfg(feature = "tracing")]
+        trace::record_result(&span, &result, started.elapsed());
+        #[cfg(feature = "metrics")]
+        metrics::record_result(endpoint, &result, started.elapsed(), coalesced);
+        #[cfg(feature = "extra-fields")]
+        if let (Some(drift), Ok((response, value))) = (&self.schema_drift, &result) {
+            drift.record(endpoint, value, &response.body);
+        }
+
+        result.map(|(_, value)| value)
     }
 
     fn area_query(area: &Area) -> String {
@@ -53,10 +116,7 @@ impl Client {
     }
 
     pub fn new() -> Self {
-        Self {
-            http: reqwest::Client::new(),
-            base_url: BASE_URL.to_string(),
-        }
+        Self::from_http_client(reqwest::Client::new())
     }
 
     /// Creates a client with a pre-configured [`reqwest::Client`].
@@ -77,21 +137,130 @@ impl Client {
         Self {
             http,
             base_url: BASE_URL.to_string(),
+            single_flight: Some(Arc::default()),
+            middleware: Arc::default(),
+            #[cfg(feature = "tracing")]
+            redact_sensitive_fields: false,
+            #[cfg(feature = "extra-fields")]
+            schema_drift: None,
         }
     }
 
+    /// Enables or disables request coalescing. Enabled by default.
+    ///
+    /// While enabled, concurrent calls for the same URL share one in-flight
+    /// HTTP request and each receive the result, or a clone of the error.
+    /// The setting is shared by clones made after it is applied.
+    pub fn with_request_coalescing(mut self, enabled: bool) -> Self {
+        self.single_flight = enabled.then(Arc::default);
+        self
+    }
+
+    /// Adds a middleware layer around every request.
+    ///
+    /// Layers run in the order they are added, so the first layer added is
+    /// the outermost. See the [`middleware`](crate::middleware) module.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use uk_police_api::middleware::{Cache, RateLimit, Retry};
+    ///
+    /// let client = uk_police_api::Client::new()
+    ///     .with_middleware(Cache::new(Duration::from_secs(3600)))
+    ///     .with_middleware(Retry::new(3))
+    ///     .with_middleware(RateLimit::default());
+    /// ```
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        Arc::make_mut(&mut self.middleware).push(Arc::new(middleware));
+        self
+    }
+
+    /// Replaces sensitive values in tracing spans with `"[redacted]"`.
+    ///
+    /// Redacted fields are the request URL, the queried location (point
+    /// coordinates or location ID) and crime persistent IDs. The area kind,
+    /// polygon vertex count and all other fields are still recorded.
+    #[cfg(feature = "tracing")]
+    pub fn with_tracing_redaction(mut self, redact: bool) -> Self {
+        self.redact_sensitive_fields = redact;
+        self
+    }
+
+    /// Enables or disables schema drift tracking. Disabled by default.
+    ///
+    /// While enabled, every successful response is compared with the models
+    /// it was decoded into, and fields the API added or left out are
+    /// recorded per endpoint. Read the results with
+    /// [`Client::schema_drift`]. Tracking is shared by clones made after it
+    /// is applied; disabling it discards the report.
+    #[cfg(feature = "extra-fields")]
+    pub fn with_schema_drift(mut self, enabled: bool) -> Self {
+        self.schema_drift = enabled.then(Arc::default);
+        self
+    }
+
+    /// Returns the new and missing fields observed so far, or an empty
+    /// report if tracking is disabled. See [`Client::with_schema_drift`].
+    #[cfg(feature = "extra-fields")]
+    pub fn schema_drift(&self) -> SchemaDriftReport {
+        self.schema_drift
+            .as_ref()
+            .map(|drift| drift.report())
+            .unwrap_or_default()
+    }
+
+    /// Points the client at a different base URL, such as a caching proxy
+    /// serving the same paths as `https://data.police.uk/api` (the default)
+    /// or a mock server. A trailing slash is ignored.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetches a path and query relative to the base URL (e.g.
+    /// `"/forces/leicestershire"`) without decoding the response.
+    ///
+    /// The request goes through the middleware chain and request coalescing
+    /// like any other call, with the endpoint name `"raw"`. Useful for
+    /// relaying responses, as the `uk-police-proxy` binary does.
+    pub async fn get_raw(&self, path_and_query: &str) -> Result<Response, Error> {
+        let path_and_query = path_and_query.trim_start_matches('/');
+        let url = format!("{}/{}", self.base_url, path_and_query);
+        self.fetch(Request::new("raw", url)).await.0
+    }
+
     /// Returns a list of all police forces.
     pub async fn forces(&self) -> Result<Vec<Force>, Error> {
         let url = format!("{}/forces", self.base_url);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("forces", url)).await
+    }
+
+    /// Fetches the live list of forces and the neighbourhoods of each
+    /// catalogue force, and compares them with the embedded
+    /// [`catalogue`](crate::catalogue). Makes one request for the force list
+    /// and one per force the catalogue knows.
+    pub async fn force_catalogue_drift(&self) -> Result<CatalogueDrift, Error> {
+        let forces = self.forces().await?;
+        let mut drift = catalogue::reconcile(&forces);
+        let mut counts = Vec::new();
+        for force in &forces {
+            if catalogue::get(force.id.as_str()).is_some() {
+                let count = self.neighbourhoods(&force.id).await?.len();
+                counts.push((&force.id, count as u32));
+            }
+        }
+        drift.neighbourhoods = catalogue::reconcile_neighbourhoods(counts);
+        Ok(drift)
     }
 
     /// Returns details for a specific police force.
-    pub async fn force(&self, id: &str) -> Result<ForceDetail, Error> {
+    pub async fn force(&self, id: &ForceId) -> Result<ForceDetail, Error> {
         let url = format!("{}/forces/{}", self.base_url, id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("force", url).force(id.as_str()))
+            .await
     }
 
     /// Returns a list of crime categories. Optionally filtered by date (format: `YYYY-MM`).
@@ -100,8 +269,8 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("?date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("crime_categories", url).month(date))
+            .await
     }
 
     /// Returns street-level crimes within a given area.
@@ -126,8 +295,13 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("street_level_crimes", url)
+                .category(category)
+                .area(area)
+                .month(date),
+        )
+        .await
     }
 
     /// Returns street-level outcomes at a given location.
@@ -149,22 +323,32 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("street_level_outcomes", url)
+                .area(area)
+                .month(date),
+        )
+        .await
     }
 
     /// Returns the date when crime data was last updated.
     pub async fn crime_last_updated(&self) -> Result<CrimeLastUpdated, Error> {
         let url = format!("{}/crime-last-updated", self.base_url);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("crime_last_updated", url)).await
+    }
+
+    /// Returns the months for which crime data is available, newest first,
+    /// with the forces that published stop and search data for each.
+    pub async fn crime_availability(&self) -> Result<Vec<CrimeAvailability>, Error> {
+        let url = format!("{}/crimes-street-dates", self.base_url);
+        self.get(Request::new("crime_availability", url)).await
     }
 
     /// Returns a list of senior officers for a given force.
-    pub async fn senior_officers(&self, force_id: &str) -> Result<Vec<SeniorOfficer>, Error> {
+    pub async fn senior_officers(&self, force_id: &ForceId) -> Result<Vec<SeniorOfficer>, Error> {
         let url = format!("{}/forces/{}/people", self.base_url, force_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("senior_officers", url).force(force_id.as_str()))
+            .await
     }
 
     /// Returns crimes at a specific location.
@@ -175,7 +359,7 @@ impl Client {
     /// * `date` - Optional month filter (format: `YYYY-MM`). Defaults to the latest available.
     pub async fn crimes_at_location(
         &self,
-        location_id: u64,
+        location_id: LocationId,
         date: Option<&str>,
     ) -> Result<Vec<Crime>, Error> {
         let mut url = format!(
@@ -185,8 +369,12 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("crimes_at_location", url)
+                .area(&Area::LocationId(location_id))
+                .month(date),
+        )
+        .await
     }
 
     /// Returns crimes that could not be mapped to a location.
@@ -199,7 +387,7 @@ impl Client {
     pub async fn crimes_no_location(
         &self,
         category: &str,
-        force: &str,
+        force: &ForceId,
         date: Option<&str>,
     ) -> Result<Vec<Crime>, Error> {
         let mut url = format!(
@@ -209,8 +397,13 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("crimes_no_location", url)
+                .category(category)
+                .force(force.as_str())
+                .month(date),
+        )
+        .await
     }
 
     /// Returns all outcomes for a specific crime.
@@ -218,78 +411,105 @@ impl Client {
     /// # Arguments
     ///
     /// * `persistent_id` - The 64-character crime identifier.
-    pub async fn outcomes_for_crime(&self, persistent_id: &str) -> Result<CrimeOutcomes, Error> {
+    pub async fn outcomes_for_crime(
+        &self,
+        persistent_id: &PersistentId,
+    ) -> Result<CrimeOutcomes, Error> {
         let url = format!("{}/outcomes-for-crime/{}", self.base_url, persistent_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("outcomes_for_crime", url).persistent_id(persistent_id.as_str()))
+            .await
     }
 
     /// Returns a list of neighbourhoods for a force.
-    pub async fn neighbourhoods(&self, force_id: &str) -> Result<Vec<Neighbourhood>, Error> {
+    pub async fn neighbourhoods(&self, force_id: &ForceId) -> Result<Vec<Neighbourhood>, Error> {
         let url = format!("{}/{}/neighbourhoods", self.base_url, force_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("neighbourhoods", url).force(force_id.as_str()))
+            .await
     }
 
     /// Returns details for a specific neighbourhood.
     pub async fn neighbourhood(
         &self,
-        force_id: &str,
-        neighbourhood_id: &str,
+        neighbourhood: &NeighbourhoodKey,
     ) -> Result<NeighbourhoodDetail, Error> {
-        let url = format!("{}/{}/{}", self.base_url, force_id, neighbourhood_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        let url = format!(
+            "{}/{}/{}",
+            self.base_url, neighbourhood.force, neighbourhood.neighbourhood
+        );
+        self.get(
+            Request::new("neighbourhood", url)
+                .force(neighbourhood.force.as_str())
+                .neighbourhood(neighbourhood.neighbourhood.as_str()),
+        )
+        .await
     }
 
     /// Returns the boundary of a neighbourhood as a list of lat/lng pairs.
     pub async fn neighbourhood_boundary(
         &self,
-        force_id: &str,
-        neighbourhood_id: &str,
+        neighbourhood: &NeighbourhoodKey,
     ) -> Result<Vec<LatLng>, Error> {
         let url = format!(
             "{}/{}/{}/boundary",
-            self.base_url, force_id, neighbourhood_id
+            self.base_url, neighbourhood.force, neighbourhood.neighbourhood
         );
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("neighbourhood_boundary", url)
+                .force(neighbourhood.force.as_str())
+                .neighbourhood(neighbourhood.neighbourhood.as_str()),
+        )
+        .await
     }
 
     /// Returns the policing team for a neighbourhood.
     pub async fn neighbourhood_team(
         &self,
-        force_id: &str,
-        neighbourhood_id: &str,
+        neighbourhood: &NeighbourhoodKey,
     ) -> Result<Vec<SeniorOfficer>, Error> {
-        let url = format!("{}/{}/{}/people", self.base_url, force_id, neighbourhood_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        let url = format!(
+            "{}/{}/{}/people",
+            self.base_url, neighbourhood.force, neighbourhood.neighbourhood
+        );
+        self.get(
+            Request::new("neighbourhood_team", url)
+                .force(neighbourhood.force.as_str())
+                .neighbourhood(neighbourhood.neighbourhood.as_str()),
+        )
+        .await
     }
 
     /// Returns events for a neighbourhood.
     pub async fn neighbourhood_events(
         &self,
-        force_id: &str,
-        neighbourhood_id: &str,
+        neighbourhood: &NeighbourhoodKey,
     ) -> Result<Vec<NeighbourhoodEvent>, Error> {
-        let url = format!("{}/{}/{}/events", self.base_url, force_id, neighbourhood_id);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        let url = format!(
+            "{}/{}/{}/events",
+            self.base_url, neighbourhood.force, neighbourhood.neighbourhood
+        );
+        self.get(
+            Request::new("neighbourhood_events", url)
+                .force(neighbourhood.force.as_str())
+                .neighbourhood(neighbourhood.neighbourhood.as_str()),
+        )
+        .await
     }
 
     /// Returns policing priorities for a neighbourhood.
     pub async fn neighbourhood_priorities(
         &self,
-        force_id: &str,
-        neighbourhood_id: &str,
+        neighbourhood: &NeighbourhoodKey,
     ) -> Result<Vec<NeighbourhoodPriority>, Error> {
         let url = format!(
             "{}/{}/{}/priorities",
-            self.base_url, force_id, neighbourhood_id
+            self.base_url, neighbourhood.force, neighbourhood.neighbourhood
         );
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("neighbourhood_priorities", url)
+                .force(neighbourhood.force.as_str())
+                .neighbourhood(neighbourhood.neighbourhood.as_str()),
+        )
+        .await
     }
 
     /// Locates the neighbourhood policing team responsible for a given point.
@@ -299,8 +519,10 @@ impl Client {
         lng: f64,
     ) -> Result<LocateNeighbourhoodResult, Error> {
         let url = format!("{}/locate-neighbourhood?q={},{}", self.base_url, lat, lng);
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("locate_neighbourhood", url).area(&Area::Point(Coordinate { lat, lng })),
+        )
+        .await
     }
 
     /// Returns stop and searches within a given area.
@@ -318,8 +540,8 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(Request::new("stops_street", url).area(area).month(date))
+            .await
     }
 
     /// Returns stop and searches at a specific location.
@@ -330,7 +552,7 @@ impl Client {
     /// * `date` - Optional month filter (format: `YYYY-MM`). Defaults to the latest available.
     pub async fn stops_at_location(
         &self,
-        location_id: u64,
+        location_id: LocationId,
         date: Option<&str>,
     ) -> Result<Vec<StopAndSearch>, Error> {
         let mut url = format!(
@@ -340,8 +562,12 @@ impl Client {
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("stops_at_location", url)
+                .area(&Area::LocationId(location_id))
+                .month(date),
+        )
+        .await
     }
 
     /// Returns stop and searches that could not be mapped to a location.
@@ -352,15 +578,19 @@ impl Client {
     /// * `date` - Optional month filter (format: `YYYY-MM`). Defaults to the latest available.
     pub async fn stops_no_location(
         &self,
-        force: &str,
+        force: &ForceId,
         date: Option<&str>,
     ) -> Result<Vec<StopAndSearch>, Error> {
         let mut url = format!("{}/stops-no-location?force={}", self.base_url, force);
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("stops_no_location", url)
+                .force(force.as_str())
+                .month(date),
+        )
+        .await
     }
 
     /// Returns stop and searches reported by a force.
@@ -371,15 +601,19 @@ impl Client {
     /// * `date` - Optional month filter (format: `YYYY-MM`). Defaults to the latest available.
     pub async fn stops_force(
         &self,
-        force: &str,
+        force: &ForceId,
         date: Option<&str>,
     ) -> Result<Vec<StopAndSearch>, Error> {
         let mut url = format!("{}/stops-force?force={}", self.base_url, force);
         if let Some(date) = date {
             url.push_str(&format!("&date={date}"));
         }
-        let response = self.http.get(&url).send().await?;
-        Self::handle_response(response).await
+        self.get(
+            Request::new("stops_force", url)
+                .force(force.as_str())
+                .month(date),
+        )
+        .await
     }
 }
 
@@ -392,15 +626,16 @@ impl Default for Client {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::Coordinate;
+    use crate::models::Unrecognised;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn test_client(uri: &str) -> Client {
-        Client {
-            http: reqwest::Client::new(),
-            base_url: uri.to_string(),
-        }
+        Client::new().with_base_url(uri)
+    }
+
+    fn force_id(id: &str) -> ForceId {
+        id.parse().unwrap()
     }
 
     #[tokio::test]
@@ -420,7 +655,7 @@ mod tests {
         let forces = client.forces().await.unwrap();
 
         assert_eq!(forces.len(), 2);
-        assert_eq!(forces[0].id, "met");
+        assert_eq!(forces[0].id.as_str(), "met");
         assert_eq!(forces[1].name, "Kent Police");
     }
 
@@ -449,9 +684,9 @@ mod tests {
             .await;
 
         let client = test_client(&server.uri());
-        let force = client.force("metropolitan").await.unwrap();
+        let force = client.force(&force_id("metropolitan")).await.unwrap();
 
-        assert_eq!(force.id, "metropolitan");
+        assert_eq!(force.id.as_str(), "metropolitan");
         assert_eq!(force.telephone, Some("101".to_string()));
         assert_eq!(force.engagement_methods.len(), 1);
         assert_eq!(force.engagement_methods[0].kind, "twitter");
@@ -599,7 +834,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let outcomes = client
-            .street_level_outcomes(&Area::LocationId(1737432), Some("2024-01"))
+            .street_level_outcomes(&Area::LocationId(LocationId(1737432)), Some("2024-01"))
             .await
             .unwrap();
 
@@ -612,6 +847,57 @@ mod tests {
         assert!(outcomes[0].person_id.is_none());
     }
 
+    #[test]
+    fn test_outcome_category_metadata() {
+        use crate::models::{OutcomeCategory, OutcomeGroup};
+
+        for category in OutcomeCategory::ALL {
+            assert_eq!(
+                category.code().parse::<OutcomeCategory>(),
+                Ok(category.clone())
+            );
+            assert_eq!(
+                category.label().parse::<OutcomeCategory>(),
+                Ok(category.clone())
+            );
+            let json = serde_json::to_string(&category).unwrap();
+            assert_eq!(json, format!("\"{category}\""));
+            let from_label: OutcomeCategory =
+                serde_json::from_value(serde_json::json!(category.label())).unwrap();
+            assert_eq!(from_label, category);
+        }
+
+        assert_eq!(
+            " offender given penalty notice ".parse(),
+            Ok(OutcomeCategory::PenaltyNoticeIssued)
+        );
+        assert!("made-up".parse::<OutcomeCategory>().is_err());
+        for value in ["under investigation", " Under-Investigation", "made-up"] {
+            assert_eq!(
+                value.parse::<OutcomeCategory>().ok(),
+                Some(OutcomeCategory::from(value)).filter(|c| !c.is_unknown())
+            );
+        }
+        assert_eq!(
+            OutcomeCategory::from("made-up"),
+            OutcomeCategory::Unknown("made-up".to_string())
+        );
+
+        let imprisoned = OutcomeCategory::Imprisoned;
+        assert_eq!(imprisoned.label(), "Offender sent to prison");
+        assert_eq!(imprisoned.group(), OutcomeGroup::Conviction);
+        assert!(imprisoned.is_terminal());
+        assert!(imprisoned.is_suspect_identified());
+
+        let no_suspect = OutcomeCategory::NoFurtherAction;
+        assert_eq!(no_suspect.group(), OutcomeGroup::NoFurtherAction);
+        assert!(no_suspect.is_terminal());
+        assert!(!no_suspect.is_suspect_identified());
+
+        assert!(!OutcomeCategory::Charged.is_terminal());
+        assert_eq!(OutcomeCategory::NotGuilty.group(), OutcomeGroup::Acquittal);
+    }
+
     #[tokio::test]
     async fn test_street_level_outcomes_by_point() {
         let server = MockServer::start().await;
@@ -651,6 +937,28 @@ mod tests {
         assert_eq!(updated.date, "2025-12-01");
     }
 
+    #[tokio::test]
+    async fn test_crime_availability() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crimes-street-dates"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "date": "2025-12", "stop-and-search": ["avon-and-somerset", "kent"] },
+                { "date": "2025-11", "stop-and-search": [] }
+            ])))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let availability = client.crime_availability().await.unwrap();
+
+        assert_eq!(availability.len(), 2);
+        assert_eq!(availability[0].date, "2025-12");
+        assert_eq!(availability[0].stop_and_search[1].as_str(), "kent");
+        assert!(availability[1].stop_and_search.is_empty());
+    }
+
     #[tokio::test]
     async fn test_senior_officers() {
         let server = MockServer::start().await;
@@ -671,7 +979,10 @@ mod tests {
             .await;
 
         let client = test_client(&server.uri());
-        let officers = client.senior_officers("metropolitan").await.unwrap();
+        let officers = client
+            .senior_officers(&force_id("metropolitan"))
+            .await
+            .unwrap();
 
         assert_eq!(officers.len(), 1);
         assert_eq!(officers[0].name, "Mark Rowley");
@@ -695,7 +1006,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let crimes = client
-            .crimes_at_location(1738842, Some("2024-01"))
+            .crimes_at_location(LocationId(1738842), Some("2024-01"))
             .await
             .unwrap();
 
@@ -712,7 +1023,7 @@ mod tests {
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
                 {
                     "category": "burglary",
-                    "persistent_id": "abc123",
+                    "persistent_id": "590d68b69228a9ff95b675bb4af591b38de561aa03129dc09a03ef34f537588c",
                     "location_subtype": "",
                     "id": 999,
                     "location": null,
@@ -727,7 +1038,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let crimes = client
-            .crimes_no_location("burglary", "metropolitan", Some("2024-01"))
+            .crimes_no_location("burglary", &force_id("metropolitan"), Some("2024-01"))
             .await
             .unwrap();
 
@@ -777,7 +1088,11 @@ mod tests {
 
         let client = test_client(&server.uri());
         let result = client
-            .outcomes_for_crime("dd6e56f90d1bdd7bc7482af17852369f263203d9a688fac42ec53bf48485d8f1")
+            .outcomes_for_crime(
+                &"dd6e56f90d1bdd7bc7482af17852369f263203d9a688fac42ec53bf48485d8f1"
+                    .parse()
+                    .unwrap(),
+            )
             .await
             .unwrap();
 
@@ -804,10 +1119,13 @@ mod tests {
             .await;
 
         let client = test_client(&server.uri());
-        let neighbourhoods = client.neighbourhoods("leicestershire").await.unwrap();
+        let neighbourhoods = client
+            .neighbourhoods(&force_id("leicestershire"))
+            .await
+            .unwrap();
 
         assert_eq!(neighbourhoods.len(), 2);
-        assert_eq!(neighbourhoods[0].id, "NC04");
+        assert_eq!(neighbourhoods[0].id.as_str(), "NC04");
         assert_eq!(neighbourhoods[1].name, "Cultural Quarter");
     }
 
@@ -851,11 +1169,11 @@ mod tests {
 
         let client = test_client(&server.uri());
         let detail = client
-            .neighbourhood("leicestershire", "NC04")
+            .neighbourhood(&"leicestershire/NC04".parse().unwrap())
             .await
             .unwrap();
 
-        assert_eq!(detail.id, "NC04");
+        assert_eq!(detail.id.as_str(), "NC04");
         assert_eq!(detail.population, Some("7985".to_string()));
         assert_eq!(detail.centre.latitude, "52.6389");
         assert_eq!(detail.links.len(), 1);
@@ -879,7 +1197,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let boundary = client
-            .neighbourhood_boundary("leicestershire", "NC04")
+            .neighbourhood_boundary(&"leicestershire/NC04".parse().unwrap())
             .await
             .unwrap();
 
@@ -907,7 +1225,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let team = client
-            .neighbourhood_team("leicestershire", "NC04")
+            .neighbourhood_team(&"leicestershire/NC04".parse().unwrap())
             .await
             .unwrap();
 
@@ -942,7 +1260,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let events = client
-            .neighbourhood_events("leicestershire", "NC04")
+            .neighbourhood_events(&"leicestershire/NC04".parse().unwrap())
             .await
             .unwrap();
 
@@ -970,7 +1288,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let priorities = client
-            .neighbourhood_priorities("leicestershire", "NC04")
+            .neighbourhood_priorities(&"leicestershire/NC04".parse().unwrap())
             .await
             .unwrap();
 
@@ -1004,8 +1322,8 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(result.force, "metropolitan");
-        assert_eq!(result.neighbourhood, "E05013806N");
+        assert_eq!(result.force.as_str(), "metropolitan");
+        assert_eq!(result.neighbourhood.as_str(), "E05013806N");
     }
 
     fn mock_stop_json() -> serde_json::Value {
@@ -1055,11 +1373,33 @@ mod tests {
             Some(crate::models::StopAndSearchType::Person)
         );
         assert_eq!(stops[0].involved_person, Some(true));
-        assert_eq!(stops[0].gender, Some("Male".to_string()));
+        assert_eq!(stops[0].gender, Some(crate::models::Gender::Male));
+        assert_eq!(
+            stops[0].age_range,
+            Some(crate::models::AgeRange::From18To24)
+        );
+        let ethnicity = stops[0].self_defined_ethnicity.as_ref().unwrap();
+        assert_eq!(
+            ethnicity,
+            &crate::models::SelfDefinedEthnicity::WhiteBritish
+        );
+        assert_eq!(ethnicity.group(), Some(crate::models::Ethnicity::White));
+        assert_eq!(
+            ethnicity.subgroup(),
+            Some("English/Welsh/Scottish/Northern Irish/British")
+        );
+        assert_eq!(
+            stops[0].legislation,
+            Some(crate::models::Legislation::MisuseOfDrugsAct1971Section23)
+        );
         assert_eq!(
             stops[0].outcome,
             Some("A no further action disposal".to_string())
         );
+        assert_eq!(
+            stops[0].stop_outcome(),
+            crate::models::StopOutcome::NoFurtherAction
+        );
     }
 
     #[tokio::test]
@@ -1074,14 +1414,14 @@ mod tests {
 
         let client = test_client(&server.uri());
         let stops = client
-            .stops_at_location(1737432, Some("2024-01"))
+            .stops_at_location(LocationId(1737432), Some("2024-01"))
             .await
             .unwrap();
 
         assert_eq!(stops.len(), 1);
         assert_eq!(
             stops[0].object_of_search,
-            Some("Controlled drugs".to_string())
+            Some(crate::models::ObjectOfSearch::ControlledDrugs)
         );
     }
 
@@ -1115,7 +1455,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let stops = client
-            .stops_no_location("leicestershire", Some("2024-01"))
+            .stops_no_location(&force_id("leicestershire"), Some("2024-01"))
             .await
             .unwrap();
 
@@ -1126,6 +1466,7 @@ mod tests {
         );
         assert!(stops[0].location.is_none());
         assert!(stops[0].outcome.is_none());
+        assert!(!stops[0].stop_outcome().is_positive());
     }
 
     #[tokio::test]
@@ -1166,7 +1507,7 @@ mod tests {
 
         let client = test_client(&server.uri());
         let stops = client
-            .stops_force("leicestershire", Some("2024-01"))
+            .stops_force(&force_id("leicestershire"), Some("2024-01"))
             .await
             .unwrap();
 
@@ -1177,11 +1518,120 @@ mod tests {
         );
         assert_eq!(stops[0].operation, Some(true));
         assert_eq!(stops[0].operation_name, Some("Operation Blitz".to_string()));
+        assert_eq!(
+            stops[0].officer_defined_ethnicity,
+            Some(crate::models::Ethnicity::Black)
+        );
         assert_eq!(stops[0].outcome, Some("Arrest".to_string()));
         assert_eq!(
             stops[0].outcome_object.as_ref().unwrap().name,
             Some("Arrest".to_string())
         );
+        assert!(stops[0].stop_outcome().is_arrest());
+        assert!(stops[0].is_outcome_linked_to_object());
+    }
+
+    #[tokio::test]
+    async fn test_stops_unrecognised_values() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/stops-force"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "type": "Person search",
+                    "involved_person": true,
+                    "datetime": "2024-01-20T14:00:00+00:00",
+                    "operation": null,
+                    "operation_name": null,
+                    "location": null,
+                    "gender": "Not stated",
+                    "age_range": "over 34",
+                    "self_defined_ethnicity": "White - Some new subgroup",
+                    "officer_defined_ethnicity": null,
+                    "legislation": "Some Future Act 2030 (section 1)",
+                    "object_of_search": "Controlled drugs",
+                    "outcome": "Arrest",
+                    "outcome_linked_to_object_of_search": null,
+                    "removal_of_more_than_outer_clothing": null
+                }])),
+            )
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let stops = client
+            .stops_force(&force_id("leicestershire"), Some("2024-01"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stops[0].gender,
+            Some(crate::models::Gender::Unknown("Not stated".to_string()))
+        );
+        let ethnicity = stops[0].self_defined_ethnicity.as_ref().unwrap();
+        assert!(ethnicity.is_unknown());
+        assert_eq!(ethnicity.group(), Some(crate::models::Ethnicity::White));
+        assert_eq!(ethnicity.subgroup(), Some("Some new subgroup"));
+        assert_eq!(
+            stops[0].legislation.as_ref().unwrap().as_str(),
+            "Some Future Act 2030 (section 1)"
+        );
+
+        let unrecognised = stops.unrecognised_values();
+        let fields: Vec<_> = unrecognised.iter().map(|u| u.field).collect();
+        assert_eq!(fields, ["gender", "legislation", "self_defined_ethnicity"]);
+        assert_eq!(unrecognised[0].value, "Not stated");
+        assert_eq!(unrecognised[0].count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_enum_values_round_trip() {
+        let server = MockServer::start().await;
+
+        let mut crimes = mock_crime_json();
+        crimes[0]["outcome_status"]["category"] = "Referred to a new scheme".into();
+        let mut second = crimes[0].clone();
+        second["id"] = 116208999.into();
+        crimes.as_array_mut().unwrap().push(second);
+
+        Mock::given(method("GET"))
+            .and(path("/crimes-street/all-crime"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&crimes))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let area = Area::LocationId(LocationId(1738842));
+        let crimes = client
+            .street_level_crimes("all-crime", &area, None)
+            .await
+            .unwrap();
+
+        let category = &crimes[0].outcome_status.as_ref().unwrap().category;
+        assert_eq!(
+            category,
+            &crate::models::OutcomeCategory::Unknown("Referred to a new scheme".to_string())
+        );
+        let json = serde_json::to_value(&crimes[0]).unwrap();
+        assert_eq!(
+            json["outcome_status"]["category"],
+            "Referred to a new scheme"
+        );
+
+        assert_eq!(
+            crimes.unrecognised_values(),
+            [crate::models::UnrecognisedValue {
+                field: "outcome_status.category",
+                value: "Referred to a new scheme".to_string(),
+                count: 2,
+            }]
+        );
+
+        let kind: crate::models::StopAndSearchType =
+            serde_json::from_value(serde_json::json!("Drone search")).unwrap();
+        assert_eq!(kind.as_str(), "Drone search");
+        assert!(kind.is_unknown());
     }
 
     #[tokio::test]
@@ -1195,10 +1645,10 @@ mod tests {
             .await;
 
         let client = test_client(&server.uri());
-        let err = client.force("nonexistent").await.unwrap_err();
+        let err = client.force(&force_id("nonexistent")).await.unwrap_err();
 
         match err {
-            Error::Api { status, body } => {
+            Error::Api { status, body, .. } => {
                 assert_eq!(status, 404);
                 assert_eq!(body, "Not Found");
             }
@@ -1220,7 +1670,7 @@ mod tests {
         let err = client.forces().await.unwrap_err();
 
         match err {
-            Error::Api { status, body } => {
+            Error::Api { status, body, .. } => {
                 assert_eq!(status, 429);
                 assert_eq!(body, "Rate limit exceeded");
             }
@@ -1228,6 +1678,79 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_concurrent_identical_requests_are_coalesced() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crime-categories"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([{ "url": "burglary", "name": "Burglary" }]))
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let results = futures_util::future::join_all((0..5).map(|_| {
+            let client = client.clone();
+            async move { client.crime_categories(None).await }
+        }))
+        .await;
+
+        for categories in results {
+            assert_eq!(categories.unwrap()[0].url, "burglary");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesced_requests_share_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_string("Service Unavailable")
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let other = client.clone();
+        let (a, b) = tokio::join!(client.forces(), other.forces());
+
+        for err in [a.unwrap_err(), b.unwrap_err()] {
+            assert!(matches!(err, Error::Api { status: 503, .. }));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_coalescing_disabled() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([]))
+                    .set_delay(std::time::Duration::from_millis(100)),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri()).with_request_coalescing(false);
+        let (a, b) = tokio::join!(client.forces(), client.forces());
+
+        assert!(a.unwrap().is_empty());
+        assert!(b.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_bad_request() {
         let server = MockServer::start().await;
@@ -1242,11 +1765,94 @@ mod tests {
         let err = client.crime_categories(None).await.unwrap_err();
 
         match err {
-            Error::Api { status, body } => {
+            Error::Api { status, body, .. } => {
                 assert_eq!(status, 400);
                 assert_eq!(body, "Bad Request");
             }
             other => panic!("expected Error::Api, got: {other}"),
         }
     }
+
+    #[tokio::test]
+    async fn test_force_catalogue_drift() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": "leicestershire", "name": "Leicestershire Police" },
+                { "id": "new-force", "name": "New Force Police" }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/leicestershire/neighbourhoods"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": "NC04", "name": "City Centre" },
+                { "id": "NC66", "name": "Cultural Quarter" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let drift = client.force_catalogue_drift().await.unwrap();
+        assert_eq!(drift.added[0].id.as_str(), "new-force");
+        let [change] = &drift.neighbourhoods[..] else {
+            panic!("expected one neighbourhood count change");
+        };
+        assert_eq!(change.id, force_id("leicestershire"));
+        assert_eq!(change.live_count, 2);
+        assert_ne!(change.catalogue_count, Some(2));
+    }
+
+    #[test]
+    fn test_typed_ids() {
+        assert!(ForceId::new("metropolitan").is_ok());
+        assert!(ForceId::new("Metropolitan").is_err());
+        assert!(ForceId::new("").is_err());
+        assert!(crate::models::NeighbourhoodId::new("NC04").is_ok());
+        assert!(crate::models::NeighbourhoodId::new("NC/04").is_err());
+        assert!(PersistentId::new("abc123").is_err());
+
+        let err = "bad id".parse::<ForceId>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid force ID: \"bad id\"");
+
+        let key: NeighbourhoodKey = "leicestershire/NC04".parse().unwrap();
+        assert_eq!(key.force, force_id("leicestershire"));
+        assert_eq!(key.neighbourhood.as_str(), "NC04");
+        assert_eq!(key.to_string(), "leicestershire/NC04");
+        assert!("leicestershire".parse::<NeighbourhoodKey>().is_err());
+
+        let result: LocateNeighbourhoodResult = serde_json::from_value(serde_json::json!({
+            "force": "metropolitan",
+            "neighbourhood": "E05013806N"
+        }))
+        .unwrap();
+        assert_eq!(result.key().to_string(), "metropolitan/E05013806N");
+
+        // IDs from the API are kept as returned, even when `new` would
+        // reject them.
+        let result: LocateNeighbourhoodResult = serde_json::from_value(serde_json::json!({
+            "force": "Not A Force",
+            "neighbourhood": "NC/04"
+        }))
+        .unwrap();
+        assert_eq!(result.key().to_string(), "Not A Force/NC/04");
+        let crime: Crime = serde_json::from_value(serde_json::json!({
+            "category": "burglary",
+            "location_type": "Force",
+            "location": null,
+            "context": "",
+            "outcome_status": null,
+            "persistent_id": "abc123",
+            "id": 1,
+            "location_subtype": "",
+            "month": "2024-01"
+        }))
+        .unwrap();
+        assert_eq!(crime.persistent_id.unwrap().as_str(), "abc123");
+
+        assert_eq!(serde_json::to_value(LocationId(1737432)).unwrap(), 1737432);
+    }
 }
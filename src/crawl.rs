@@ -0,0 +1,604 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::models::{
+    Force, ForceDetail, ForceId, LatLng, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent,
+    NeighbourhoodId, NeighbourhoodKey, NeighbourhoodPriority, SeniorOfficer,
+};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// A single request in the force and neighbourhood hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CrawlNode {
+    /// The list of all forces.
+    Forces,
+    /// Details for a force.
+    Force { force_id: ForceId },
+    /// Senior officers for a force.
+    SeniorOfficers { force_id: ForceId },
+    /// The list of neighbourhoods for a force.
+    Neighbourhoods { force_id: ForceId },
+    /// Details for a neighbourhood.
+    Neighbourhood {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+    },
+    /// Boundary of a neighbourhood.
+    NeighbourhoodBoundary {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+    },
+    /// Policing team for a neighbourhood.
+    NeighbourhoodTeam {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+    },
+    /// Events for a neighbourhood.
+    NeighbourhoodEvents {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+    },
+    /// Policing priorities for a neighbourhood.
+    NeighbourhoodPriorities {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+    },
+}
+
+impl fmt::Display for CrawlNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Forces => write!(f, "forces"),
+            Self::Force { force_id } => write!(f, "forces/{force_id}"),
+            Self::SeniorOfficers { force_id } => write!(f, "forces/{force_id}/people"),
+            Self::Neighbourhoods { force_id } => write!(f, "{force_id}/neighbourhoods"),
+            Self::Neighbourhood {
+                force_id,
+                neighbourhood_id,
+            } => write!(f, "{force_id}/{neighbourhood_id}"),
+            Self::NeighbourhoodBoundary {
+                force_id,
+                neighbourhood_id,
+            } => write!(f, "{force_id}/{neighbourhood_id}/boundary"),
+            Self::NeighbourhoodTeam {
+                force_id,
+                neighbourhood_id,
+            } => write!(f, "{force_id}/{neighbourhood_id}/people"),
+            Self::NeighbourhoodEvents {
+                force_id,
+                neighbourhood_id,
+            } => write!(f, "{force_id}/{neighbourhood_id}/events"),
+            Self::NeighbourhoodPriorities {
+                force_id,
+                neighbourhood_id,
+            } => write!(f, "{force_id}/{neighbourhood_id}/priorities"),
+        }
+    }
+}
+
+/// A typed result yielded by a [`Crawl`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrawlItem {
+    /// The list of all forces.
+    Forces(Vec<Force>),
+    /// Details for a force.
+    Force(Box<ForceDetail>),
+    /// Senior officers for a force.
+    SeniorOfficers {
+        force_id: ForceId,
+        officers: Vec<SeniorOfficer>,
+    },
+    /// The list of neighbourhoods for a force.
+    Neighbourhoods {
+        force_id: ForceId,
+        neighbourhoods: Vec<Neighbourhood>,
+    },
+    /// Details for a neighbourhood.
+    Neighbourhood {
+        force_id: ForceId,
+        detail: Box<NeighbourhoodDetail>,
+    },
+    /// Boundary of a neighbourhood.
+    NeighbourhoodBoundary {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+        boundary: Vec<LatLng>,
+    },
+    /// Policing team for a neighbourhood.
+    NeighbourhoodTeam {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+        team: Vec<SeniorOfficer>,
+    },
+    /// Events for a neighbourhood.
+    NeighbourhoodEvents {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+        events: Vec<NeighbourhoodEvent>,
+    },
+    /// Policing priorities for a neighbourhood.
+    NeighbourhoodPriorities {
+        force_id: ForceId,
+        neighbourhood_id: NeighbourhoodId,
+        priorities: Vec<NeighbourhoodPriority>,
+    },
+}
+
+impl CrawlItem {
+    /// Nodes discovered by this item that still need to be fetched.
+    fn children(&self) -> Vec<CrawlNode> {
+        match self {
+            Self::Forces(forces) => forces
+                .iter()
+                .flat_map(|force| {
+                    let force_id = force.id.clone();
+                    [
+                        CrawlNode::Force {
+                            force_id: force_id.clone(),
+                        },
+                        CrawlNode::SeniorOfficers {
+                            force_id: force_id.clone(),
+                        },
+                        CrawlNode::Neighbourhoods { force_id },
+                    ]
+                })
+                .collect(),
+            Self::Neighbourhoods {
+                force_id,
+                neighbourhoods,
+            } => neighbourhoods
+                .iter()
+                .flat_map(|n| {
+                    let (force_id, neighbourhood_id) = (force_id.clone(), n.id.clone());
+                    [
+                        CrawlNode::Neighbourhood {
+                            force_id: force_id.clone(),
+                            neighbourhood_id: neighbourhood_id.clone(),
+                        },
+                        CrawlNode::NeighbourhoodBoundary {
+                            force_id: force_id.clone(),
+                            neighbourhood_id: neighbourhood_id.clone(),
+                        },
+                        CrawlNode::NeighbourhoodTeam {
+                            force_id: force_id.clone(),
+                            neighbourhood_id: neighbourhood_id.clone(),
+                        },
+                        CrawlNode::NeighbourhoodEvents {
+                            force_id: force_id.clone(),
+                            neighbourhood_id: neighbourhood_id.clone(),
+                        },
+                        CrawlNode::NeighbourhoodPriorities {
+                            force_id,
+                            neighbourhood_id,
+                        },
+                    ]
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A node that could not be fetched during a crawl.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to crawl {node}: {error}")]
+pub struct CrawlFailure {
+    /// The node that failed.
+    pub node: CrawlNode,
+    /// The underlying error.
+    pub error: Error,
+}
+
+/// Record of a failed node kept in a [`CrawlSummary`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedNode {
+    /// The node that failed.
+    pub node: CrawlNode,
+    /// The error message.
+    pub error: String,
+}
+
+/// Summary of a crawl so far.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlSummary {
+    /// Number of nodes fetched successfully, including any from a resumed checkpoint.
+    pub completed: usize,
+    /// Nodes that failed, in the order they failed.
+    pub failed: Vec<FailedNode>,
+}
+
+/// A serializable snapshot of crawl progress, used to resume after a failure.
+///
+/// Failed and not yet fetched nodes are both kept in `pending`, so resuming
+/// from a checkpoint retries failures and skips everything already fetched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Nodes that have been fetched successfully.
+    pub completed: BTreeSet<CrawlNode>,
+    /// Nodes that have been discovered but not fetched successfully.
+    pub pending: Vec<CrawlNode>,
+}
+
+impl Checkpoint {
+    /// Returns `true` if there is nothing left to fetch.
+    pub fn is_finished(&self) -> bool {
+        !self.completed.is_empty() && self.pending.is_empty()
+    }
+}
+
+/// Walks every force and neighbourhood with bounded concurrency.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use uk_police_api::{Client, Crawler};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut crawl = Crawler::new(Client::new()).concurrency(8).crawl();
+/// while let Some(item) = crawl.next().await {
+///     match item {
+///         Ok(item) => println!("{item:?}"),
+///         Err(failure) => eprintln!("{failure}"),
+///     }
+/// }
+/// println!("{} failed", crawl.summary().failed.len());
+/// # }
+/// ```
+pub struct Crawler {
+    client: Client,
+    concurrency: usize,
+    checkpoint: Checkpoint,
+}
+
+impl Crawler {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            concurrency: DEFAULT_CONCURRENCY,
+            checkpoint: Checkpoint::default(),
+        }
+    }
+
+    /// Sets the maximum number of requests in flight at once. Defaults to 4.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Resumes from a checkpoint taken by [`Crawl::checkpoint`].
+    pub fn resume(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Starts the crawl.
+    pub fn crawl(self) -> Crawl {
+        let Checkpoint { completed, pending } = self.checkpoint;
+        let queue = if completed.is_empty() && pending.is_empty() {
+            VecDeque::from([CrawlNode::Forces])
+        } else {
+            pending.into()
+        };
+        Crawl {
+            client: self.client,
+            concurrency: self.concurrency,
+            queue,
+            in_flight: FuturesUnordered::new(),
+            in_flight_nodes: BTreeSet::new(),
+            completed,
+            failed: Vec::new(),
+        }
+    }
+}
+
+type NodeFuture = BoxFuture<'static, (CrawlNode, Result<CrawlItem, Error>)>;
+
+/// A running crawl. Yields each fetched item or failed node as it completes.
+pub struct Crawl {
+    client: Client,
+    concurrency: usize,
+    queue: VecDeque<CrawlNode>,
+    in_flight: FuturesUnordered<NodeFuture>,
+    in_flight_nodes: BTreeSet<CrawlNode>,
+    completed: BTreeSet<CrawlNode>,
+    failed: Vec<FailedNode>,
+}
+
+impl Crawl {
+    /// Returns a checkpoint that can be passed to [`Crawler::resume`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        let pending = self
+            .failed
+            .iter()
+            .map(|f| f.node.clone())
+            .chain(self.in_flight_nodes.iter().cloned())
+            .chain(self.queue.iter().cloned())
+            .collect();
+        Checkpoint {
+            completed: self.completed.clone(),
+            pending,
+        }
+    }
+
+    /// Returns the number of completed nodes and every node that has failed.
+    pub fn summary(&self) -> CrawlSummary {
+        CrawlSummary {
+            completed: self.completed.len(),
+            failed: self.failed.clone(),
+        }
+    }
+
+    fn fill(&mut self) {
+        while self.in_flight.len() < self.concurrency {
+            let Some(node) = self.queue.pop_front() else {
+                break;
+            };
+            if self.completed.contains(&node) || !self.in_flight_nodes.insert(node.clone()) {
+                continue;
+            }
+            self.in_flight.push(fetch(self.client.clone(), node));
+        }
+    }
+}
+
+impl Stream for Crawl {
+    type Item = Result<CrawlItem, CrawlFailure>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.fill();
+        let Some((node, result)) = ready!(this.in_flight.poll_next_unpin(cx)) else {
+            return Poll::Ready(None);
+        };
+        this.in_flight_nodes.remove(&node);
+        match result {
+            Ok(item) => {
+                this.queue.extend(item.children());
+                this.completed.insert(node);
+                Poll::Ready(Some(Ok(item)))
+            }
+            Err(error) => {
+                this.failed.push(FailedNode {
+                    node: node.clone(),
+                    error: error.to_string(),
+                });
+                Poll::Ready(Some(Err(CrawlFailure { node, error })))
+            }
+        }
+    }
+}
+
+fn fetch(client: Client, node: CrawlNode) -> NodeFuture {
+    Box::pin(async move {
+        let result =
+            match &node {
+                CrawlNode::Forces => client.forces().await.map(CrawlItem::Forces),
+                CrawlNode::Force { force_id } => client
+                    .force(force_id)
+                    .await
+                    .map(|detail| CrawlItem::Force(Box::new(detail))),
+                CrawlNode::SeniorOfficers { force_id } => client
+                    .senior_officers(force_id)
+                    .await
+                    .map(|officers| CrawlItem::SeniorOfficers {
+                        force_id: force_id.clone(),
+                        officers,
+                    }),
+                CrawlNode::Neighbourhoods { force_id } => client
+                    .neighbourhoods(force_id)
+                    .await
+                    .map(|neighbourhoods| CrawlItem::Neighbourhoods {
+                        force_id: force_id.clone(),
+                        neighbourhoods,
+                    }),
+                CrawlNode::Neighbourhood {
+                    force_id,
+                    neighbourhood_id,
+                } => client
+                    .neighbourhood(&NeighbourhoodKey::new(
+                        force_id.clone(),
+                        neighbourhood_id.clone(),
+                    ))
+                    .await
+                    .map(|detail| CrawlItem::Neighbourhood {
+                        force_id: force_id.clone(),
+                        detail: Box::new(detail),
+                    }),
+                CrawlNode::NeighbourhoodBoundary {
+                    force_id,
+                    neighbourhood_id,
+                } => client
+                    .neighbourhood_boundary(&NeighbourhoodKey::new(
+                        force_id.clone(),
+                        neighbourhood_id.clone(),
+                    ))
+                    .await
+                    .map(|boundary| CrawlItem::NeighbourhoodBoundary {
+                        force_id: force_id.clone(),
+                        neighbourhood_id: neighbourhood_id.clone(),
+                        boundary,
+                    }),
+                CrawlNode::NeighbourhoodTeam {
+                    force_id,
+                    neighbourhood_id,
+                } => client
+                    .neighbourhood_team(&NeighbourhoodKey::new(
+                        force_id.clone(),
+                        neighbourhood_id.clone(),
+                    ))
+                    .await
+                    .map(|team| CrawlItem::NeighbourhoodTeam {
+                        force_id: force_id.clone(),
+                        neighbourhood_id: neighbourhood_id.clone(),
+                        team,
+                    }),
+                CrawlNode::NeighbourhoodEvents {
+                    force_id,
+                    neighbourhood_id,
+                } => client
+                    .neighbourhood_events(&NeighbourhoodKey::new(
+                        force_id.clone(),
+                        neighbourhood_id.clone(),
+                    ))
+                    .await
+                    .map(|events| CrawlItem::NeighbourhoodEvents {
+                        force_id: force_id.clone(),
+                        neighbourhood_id: neighbourhood_id.clone(),
+                        events,
+                    }),
+                CrawlNode::NeighbourhoodPriorities {
+                    force_id,
+                    neighbourhood_id,
+                } => client
+                    .neighbourhood_priorities(&NeighbourhoodKey::new(
+                        force_id.clone(),
+                        neighbourhood_id.clone(),
+                    ))
+                    .await
+                    .map(|priorities| CrawlItem::NeighbourhoodPriorities {
+                        force_id: force_id.clone(),
+                        neighbourhood_id: neighbourhood_id.clone(),
+                        priorities,
+                    }),
+            };
+        (node, result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_json(server: &MockServer, route: &str, body: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    async fn mock_force_tree(server: &MockServer) {
+        mock_json(
+            server,
+            "/forces",
+            serde_json::json!([{ "id": "leicestershire", "name": "Leicestershire Police" }]),
+        )
+        .await;
+        mock_json(
+            server,
+            "/forces/leicestershire",
+            serde_json::json!({
+                "id": "leicestershire",
+                "name": "Leicestershire Police",
+                "description": null,
+                "url": null,
+                "telephone": "101",
+                "engagement_methods": []
+            }),
+        )
+        .await;
+        mock_json(
+            server,
+            "/forces/leicestershire/people",
+            serde_json::json!([]),
+        )
+        .await;
+        mock_json(
+            server,
+            "/leicestershire/neighbourhoods",
+            serde_json::json!([{ "id": "NC04", "name": "City Centre" }]),
+        )
+        .await;
+        mock_json(
+            server,
+            "/leicestershire/NC04/boundary",
+            serde_json::json!([{ "latitude": "52.6394", "longitude": "-1.1459" }]),
+        )
+        .await;
+        mock_json(server, "/leicestershire/NC04/people", serde_json::json!([])).await;
+        mock_json(server, "/leicestershire/NC04/events", serde_json::json!([])).await;
+        mock_json(
+            server,
+            "/leicestershire/NC04/priorities",
+            serde_json::json!([]),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_crawl_reports_failures_and_resumes() {
+        let server = MockServer::start().await;
+        mock_force_tree(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/leicestershire/NC04"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new().with_base_url(server.uri());
+        let mut crawl = Crawler::new(client.clone()).concurrency(2).crawl();
+        let mut fetched = 0;
+        while let Some(item) = crawl.next().await {
+            if item.is_ok() {
+                fetched += 1;
+            }
+        }
+
+        let summary = crawl.summary();
+        assert_eq!(fetched, 8);
+        assert_eq!(summary.completed, 8);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(
+            summary.failed[0].node,
+            CrawlNode::Neighbourhood {
+                force_id: "leicestershire".parse().unwrap(),
+                neighbourhood_id: "NC04".parse().unwrap(),
+            }
+        );
+
+        mock_json(
+            &server,
+            "/leicestershire/NC04",
+            serde_json::json!({
+                "id": "NC04",
+                "name": "City Centre",
+                "description": null,
+                "population": "7985",
+                "url_force": null,
+                "contact_details": {},
+                "centre": { "latitude": "52.6389", "longitude": "-1.1350" },
+                "links": [],
+                "locations": []
+            }),
+        )
+        .await;
+
+        let checkpoint = crawl.checkpoint();
+        assert_eq!(checkpoint.pending.len(), 1);
+
+        let mut resumed = Crawler::new(client).resume(checkpoint).crawl();
+        let items: Vec<_> = (&mut resumed).collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(matches!(
+            items[0],
+            Ok(CrawlItem::Neighbourhood { ref detail, .. }) if detail.id.as_str() == "NC04"
+        ));
+        assert!(resumed.summary().failed.is_empty());
+        assert!(resumed.checkpoint().is_finished());
+    }
+}
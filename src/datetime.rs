@@ -0,0 +1,241 @@
+//! Parsed date/time accessors, available with the `chrono` feature.
+//!
+//! The API mixes timestamps with an explicit offset (stop and search
+//! `datetime`, e.g. `2024-01-15T12:30:00+00:00`) and naive local timestamps
+//! (neighbourhood events and priorities, e.g. `2024-09-17T17:00:00`). Naive
+//! values are interpreted as UK local time, so every accessor returns a
+//! [`DateTime<FixedOffset>`] with the correct GMT or BST offset.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use uk_police_api::datetime::stops_by_hour;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), uk_police_api::Error> {
+//! let client = uk_police_api::Client::new();
+//! let stops = client
+//!     .stops_force(&"leicestershire".parse().unwrap(), Some("2024-01"))
+//!     .await?;
+//! let by_hour = stops_by_hour(&stops);
+//! println!("{} stops between 22:00 and 23:00", by_hour[22]);
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike, Utc, Weekday,
+};
+
+use crate::models::{NeighbourhoodEvent, NeighbourhoodPriority, StopAndSearch};
+
+const NAIVE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parses a timestamp as returned by the API.
+///
+/// Accepts RFC 3339 timestamps with an offset, naive timestamps (with a `T`
+/// or space separator) and plain dates. Naive values are interpreted as UK
+/// local time; plain dates as local midnight.
+pub fn parse_api_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime);
+    }
+    let naive = NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })?;
+    uk_offset_local(naive).from_local_datetime(&naive).single()
+}
+
+/// Converts a timestamp to UK local time (GMT or BST).
+pub fn to_uk_local(datetime: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+    let utc = datetime.with_timezone(&Utc).naive_utc();
+    datetime.with_timezone(&uk_offset_utc(utc))
+}
+
+/// Counts stops by hour of day in UK local time. Index 0 is midnight to 1am.
+/// Stops without a parseable `datetime` are skipped.
+pub fn stops_by_hour(stops: &[StopAndSearch]) -> [usize; 24] {
+    let mut counts = [0; 24];
+    for hour in stops.iter().filter_map(StopAndSearch::hour_of_day) {
+        counts[hour as usize] += 1;
+    }
+    counts
+}
+
+/// Counts stops by day of week in UK local time. Index 0 is Monday.
+/// Stops without a parseable `datetime` are skipped.
+pub fn stops_by_weekday(stops: &[StopAndSearch]) -> [usize; 7] {
+    let mut counts = [0; 7];
+    for weekday in stops.iter().filter_map(StopAndSearch::weekday) {
+        counts[weekday.num_days_from_monday() as usize] += 1;
+    }
+    counts
+}
+
+impl StopAndSearch {
+    /// Parsed `datetime` of the stop.
+    pub fn parsed_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.datetime.as_deref().and_then(parse_api_datetime)
+    }
+
+    /// Hour of day (0-23) the stop took place, in UK local time.
+    pub fn hour_of_day(&self) -> Option<u32> {
+        self.parsed_datetime().map(|dt| to_uk_local(dt).hour())
+    }
+
+    /// Day of week the stop took place, in UK local time.
+    pub fn weekday(&self) -> Option<Weekday> {
+        self.parsed_datetime().map(|dt| to_uk_local(dt).weekday())
+    }
+}
+
+impl NeighbourhoodEvent {
+    /// Parsed `start_date` of the event.
+    pub fn parsed_start_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.start_date.as_deref().and_then(parse_api_datetime)
+    }
+
+    /// Parsed `end_date` of the event.
+    pub fn parsed_end_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.end_date.as_deref().and_then(parse_api_datetime)
+    }
+}
+
+impl NeighbourhoodPriority {
+    /// Parsed `issue_date` of the priority.
+    pub fn parsed_issue_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.issue_date.as_deref().and_then(parse_api_datetime)
+    }
+
+    /// Parsed `action_date` of the priority.
+    pub fn parsed_action_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.action_date.as_deref().and_then(parse_api_datetime)
+    }
+}
+
+fn gmt() -> FixedOffset {
+    FixedOffset::east_opt(0).unwrap()
+}
+
+fn bst() -> FixedOffset {
+    FixedOffset::east_opt(3600).unwrap()
+}
+
+/// The instant BST starts or ends in `year`: 01:00 UTC on the last Sunday of `month`.
+fn last_sunday_1am_utc(year: i32, month: u32) -> NaiveDateTime {
+    let first_of_next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+    .unwrap();
+    let last = first_of_next - Duration::days(1);
+    let sunday = last - Duration::days(i64::from(last.weekday().num_days_from_sunday()));
+    sunday.and_hms_opt(1, 0, 0).unwrap()
+}
+
+/// UK offset in effect at a UTC instant.
+fn uk_offset_utc(utc: NaiveDateTime) -> FixedOffset {
+    let year = utc.year();
+    if utc >= last_sunday_1am_utc(year, 3) && utc < last_sunday_1am_utc(year, 10) {
+        bst()
+    } else {
+        gmt()
+    }
+}
+
+/// UK offset in effect at a local wall-clock time. Times in the skipped hour
+/// in March are treated as BST and times in the repeated hour in October as
+/// GMT.
+fn uk_offset_local(local: NaiveDateTime) -> FixedOffset {
+    // Both changes happen at 01:00 local time: forward to 02:00 BST in March,
+    // and from 02:00 BST back to 01:00 GMT in October.
+    let year = local.year();
+    if local >= last_sunday_1am_utc(year, 3) && local < last_sunday_1am_utc(year, 10) {
+        bst()
+    } else {
+        gmt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(datetime: &str) -> StopAndSearch {
+        serde_json::from_value(serde_json::json!({
+            "type": "Person search",
+            "involved_person": true,
+            "datetime": datetime,
+            "operation": null,
+            "operation_name": null,
+            "location": null,
+            "gender": null,
+            "age_range": null,
+            "self_defined_ethnicity": null,
+            "officer_defined_ethnicity": null,
+            "legislation": null,
+            "object_of_search": null,
+            "outcome": false,
+            "outcome_linked_to_object_of_search": null,
+            "removal_of_more_than_outer_clothing": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parses_offset_and_naive_values() {
+        let with_offset = parse_api_datetime("2024-01-15T12:30:00+00:00").unwrap();
+        assert_eq!(with_offset.offset(), &gmt());
+
+        let summer = parse_api_datetime("2024-09-17T17:00:00").unwrap();
+        assert_eq!(summer.offset(), &bst());
+        assert_eq!(summer.hour(), 17);
+
+        let winter = parse_api_datetime("2024-12-01T09:00:00").unwrap();
+        assert_eq!(winter.offset(), &gmt());
+
+        let date = parse_api_datetime("2024-07-01").unwrap();
+        assert_eq!(date.hour(), 0);
+        assert!(parse_api_datetime("not a date").is_none());
+    }
+
+    #[test]
+    fn test_bst_boundaries() {
+        // BST 2024 ran from 31 March 01:00 UTC to 27 October 01:00 UTC.
+        let before = parse_api_datetime("2024-03-31T00:59:00+00:00").unwrap();
+        let after = parse_api_datetime("2024-03-31T01:00:00+00:00").unwrap();
+        assert_eq!(to_uk_local(before).hour(), 0);
+        assert_eq!(to_uk_local(after).hour(), 2);
+
+        let end = parse_api_datetime("2024-10-27T01:00:00+00:00").unwrap();
+        assert_eq!(to_uk_local(end).offset(), &gmt());
+    }
+
+    #[test]
+    fn test_buckets_stops_in_local_time() {
+        let stops = [
+            stop("2024-06-14T23:30:00+00:00"),
+            stop("2024-01-15T12:30:00+00:00"),
+            stop("2024-01-15T12:45:00+00:00"),
+            stop("garbage"),
+        ];
+
+        let by_hour = stops_by_hour(&stops);
+        assert_eq!(by_hour[0], 1);
+        assert_eq!(by_hour[12], 2);
+        assert_eq!(by_hour.iter().sum::<usize>(), 3);
+
+        // 23:30 UTC on Friday 14 June is 00:30 BST on Saturday.
+        let by_weekday = stops_by_weekday(&stops);
+        assert_eq!(by_weekday[Weekday::Sat.num_days_from_monday() as usize], 1);
+        assert_eq!(by_weekday[Weekday::Mon.num_days_from_monday() as usize], 2);
+    }
+}
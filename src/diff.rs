@@ -0,0 +1,406 @@
+//! Record-level differences between two snapshots of the same query.
+//!
+//! Forces sometimes revise earlier months, so fetching the same month twice
+//! can return different records. [`diff`] matches records across the two
+//! snapshots and reports which were added, removed or modified, with the
+//! fields that changed.
+//!
+//! Records are matched by the keys returned from [`Diffable::keys`], tried
+//! in order: crimes and outcomes by `persistent_id`, falling back to the
+//! crime `id`; stop and searches, which have no ID, by their time, place
+//! and subject. Records with duplicate keys are paired in order.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = uk_police_api::Client::new();
+//! let force = "leicestershire".parse()?;
+//! let before = client.crimes_no_location("all-crime", &force, Some("2024-01")).await?;
+//! // ...later...
+//! let after = client.crimes_no_location("all-crime", &force, Some("2024-01")).await?;
+//!
+//! let changes = uk_police_api::diff::diff(&before, &after);
+//! for update in changes.outcome_updates() {
+//!     println!("{}: {:?} -> {:?}", update.crime.id, update.previous, update.current);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{Crime, Outcome, OutcomeStatus, PersistentId, StopAndSearch};
+
+/// A key that identifies the same record in two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecordKey {
+    /// A crime's persistent ID.
+    PersistentId(PersistentId),
+    /// A crime's numeric ID.
+    Id(u64),
+    /// Identifying field values, for records without an ID.
+    Fields(String),
+}
+
+impl RecordKey {
+    fn kind(&self) -> KeyKind {
+        match self {
+            Self::PersistentId(_) => KeyKind::PersistentId,
+            Self::Id(_) => KeyKind::Id,
+            Self::Fields(_) => KeyKind::Fields,
+        }
+    }
+}
+
+/// The variant of a [`RecordKey`], without its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    PersistentId,
+    Id,
+    Fields,
+}
+
+impl KeyKind {
+    /// Kinds of key in the order they are tried.
+    const MATCH_ORDER: [Self; 3] = [Self::PersistentId, Self::Id, Self::Fields];
+}
+
+/// A record that can be matched across snapshots.
+pub trait Diffable: Serialize {
+    /// Keys to match the record by. Records are matched on persistent IDs
+    /// first, then any still unmatched on numeric IDs, then on fields.
+    fn keys(&self) -> Vec<RecordKey>;
+}
+
+fn crime_keys(crime: &Crime) -> Vec<RecordKey> {
+    let mut keys = Vec::with_capacity(2);
+    if let Some(persistent_id) = &crime.persistent_id {
+        keys.push(RecordKey::PersistentId(persistent_id.clone()));
+    }
+    keys.push(RecordKey::Id(crime.id));
+    keys
+}
+
+impl Diffable for Crime {
+    fn keys(&self) -> Vec<RecordKey> {
+        crime_keys(self)
+    }
+}
+
+impl Diffable for Outcome {
+    fn keys(&self) -> Vec<RecordKey> {
+        crime_keys(&self.crime)
+    }
+}
+
+impl Diffable for StopAndSearch {
+    fn keys(&self) -> Vec<RecordKey> {
+        vec![RecordKey::Fields(self.identity())]
+    }
+}
+
+/// A field whose value differs between two versions of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Dotted path of the field, e.g. `outcome_status.category`.
+    pub field: String,
+    /// Value before, or `null` if the field was absent.
+    pub before: Value,
+    /// Value after, or `null` if the field is absent.
+    pub after: Value,
+}
+
+/// A record present in both snapshots with different field values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modified<'a, T> {
+    pub before: &'a T,
+    pub after: &'a T,
+    /// Changed fields, sorted by path.
+    pub changes: Vec<FieldChange>,
+}
+
+impl<T> Modified<'_, T> {
+    /// Returns the change to `field`, if it changed.
+    pub fn change(&self, field: &str) -> Option<&FieldChange> {
+        self.changes.iter().find(|change| change.field == field)
+    }
+}
+
+/// Differences between two snapshots, returned by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff<'a, T> {
+    /// Records only in the newer snapshot, in its order.
+    pub added: Vec<&'a T>,
+    /// Records only in the older snapshot, in its order.
+    pub removed: Vec<&'a T>,
+    /// Records in both with changed fields, in the older snapshot's order.
+    pub modified: Vec<Modified<'a, T>>,
+}
+
+impl<T> Diff<'_, T> {
+    /// Returns `true` if the snapshots hold the same records.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// A crime whose latest outcome changed between snapshots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutcomeUpdate<'a> {
+    /// The crime as it is in the newer snapshot.
+    pub crime: &'a Crime,
+    pub previous: Option<&'a OutcomeStatus>,
+    pub current: Option<&'a OutcomeStatus>,
+}
+
+impl<'a> Diff<'a, Crime> {
+    /// Returns the modified crimes whose `outcome_status` changed.
+    pub fn outcome_updates(&self) -> Vec<OutcomeUpdate<'a>> {
+        self.modified
+            .iter()
+            .filter(|modified| modified.before.outcome_status != modified.after.outcome_status)
+            .map(|modified| OutcomeUpdate {
+                crime: modified.after,
+                previous: modified.before.outcome_status.as_ref(),
+                current: modified.after.outcome_status.as_ref(),
+            })
+            .collect()
+    }
+}
+
+/// Compares an older snapshot of records with a newer one.
+pub fn diff<'a, T: Diffable>(before: &'a [T], after: &'a [T]) -> Diff<'a, T> {
+    let before_keys: Vec<Vec<RecordKey>> = before.iter().map(Diffable::keys).collect();
+    let after_keys: Vec<Vec<RecordKey>> = after.iter().map(Diffable::keys).collect();
+
+    let mut partner: Vec<Option<usize>> = vec![None; before.len()];
+    let mut matched_after = vec![false; after.len()];
+    for kind in KeyKind::MATCH_ORDER {
+        let key_of = |keys: &[RecordKey]| keys.iter().find(|key| key.kind() == kind).cloned();
+        let mut unmatched: HashMap<RecordKey, VecDeque<usize>> = HashMap::new();
+        for (index, keys) in after_keys.iter().enumerate() {
+            if matched_after[index] {
+                continue;
+            }
+            if let Some(key) = key_of(keys) {
+                unmatched.entry(key).or_default().push_back(index);
+            }
+        }
+        for (index, keys) in before_keys.iter().enumerate() {
+            if partner[index].is_some() {
+                continue;
+            }
+            let Some(key) = key_of(keys) else { continue };
+            if let Some(other) = unmatched.get_mut(&key).and_then(VecDeque::pop_front) {
+                partner[index] = Some(other);
+                matched_after[other] = true;
+            }
+        }
+    }
+
+    let mut result = Diff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+    };
+    for (index, record) in before.iter().enumerate() {
+        match partner[index] {
+            None => result.removed.push(record),
+            Some(other) => {
+                let changes = field_changes(record, &after[other]);
+                if !changes.is_empty() {
+                    result.modified.push(Modified {
+                        before: record,
+                        after: &after[other],
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    result.added = after
+        .iter()
+        .zip(&matched_after)
+        .filter(|(_, matched)| !**matched)
+        .map(|(record, _)| record)
+        .collect();
+    result
+}
+
+/// Compares two records field by field. Nested objects are compared by
+/// their leaf fields; arrays are compared whole.
+pub fn field_changes<T: Serialize>(before: &T, after: &T) -> Vec<FieldChange> {
+    let mut before_fields = Vec::new();
+    let mut after_fields = Vec::new();
+    flatten(
+        "",
+        serde_json::to_value(before).unwrap_or(Value::Null),
+        &mut before_fields,
+    );
+    flatten(
+        "",
+        serde_json::to_value(after).unwrap_or(Value::Null),
+        &mut after_fields,
+    );
+    let mut before_fields: HashMap<String, Value> = before_fields.into_iter().collect();
+
+    let mut changes = Vec::new();
+    for (field, after) in after_fields {
+        let before = before_fields.remove(&field).unwrap_or(Value::Null);
+        if before != after {
+            changes.push(FieldChange {
+                field,
+                before,
+                after,
+            });
+        }
+    }
+    changes.extend(
+        before_fields
+            .into_iter()
+            .filter(|(_, before)| !before.is_null())
+            .map(|(field, before)| FieldChange {
+                field,
+                before,
+                after: Value::Null,
+            }),
+    );
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+fn flatten(prefix: &str, value: Value, fields: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                let path = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, value, fields);
+            }
+        }
+        other => fields.push((prefix.to_string(), other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PERSISTENT_ID: &str = "dd6e56f90d1bdd7bc7482af17852369f263203d9a688fac42ec53bf48485d8f1";
+
+    fn crime(id: u64, persistent_id: &str, outcome: Option<&str>) -> Crime {
+        serde_json::from_value(json!({
+            "category": "burglary",
+            "persistent_id": persistent_id,
+            "location_subtype": "",
+            "id": id,
+            "location": {
+                "latitude": "52.6297",
+                "street": { "id": 100, "name": "On or near High Street" },
+                "longitude": "-1.1316"
+            },
+            "context": "",
+            "month": "2024-01",
+            "location_type": "Force",
+            "outcome_status": outcome.map(|category| json!({ "category": category, "date": "2024-02" }))
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_crimes() {
+        let before = [
+            crime(1, PERSISTENT_ID, Some("Under investigation")),
+            crime(2, "", None),
+            crime(3, "", None),
+        ];
+        // The API reissued crime 1 with a new numeric ID but the same
+        // persistent ID, and gave it an outcome.
+        let after = [
+            crime(10, PERSISTENT_ID, Some("Offender given a caution")),
+            crime(2, "", None),
+            crime(4, "", None),
+        ];
+        let changes = diff(&before, &after);
+        assert_eq!(changes.added, [&after[2]]);
+        assert_eq!(changes.removed, [&before[2]]);
+        assert_eq!(changes.modified.len(), 1);
+
+        let modified = &changes.modified[0];
+        assert_eq!(
+            modified.changes,
+            [
+                FieldChange {
+                    field: "id".to_string(),
+                    before: json!(1),
+                    after: json!(10),
+                },
+                FieldChange {
+                    field: "outcome_status.category".to_string(),
+                    before: json!("under-investigation"),
+                    after: json!("cautioned"),
+                },
+            ]
+        );
+
+        let updates = changes.outcome_updates();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].crime.id, 10);
+        assert_eq!(updates[0].current.unwrap().category.as_str(), "cautioned");
+
+        assert!(diff(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_new_outcome_status() {
+        let before = [crime(1, PERSISTENT_ID, None)];
+        let after = [crime(1, PERSISTENT_ID, Some("Under investigation"))];
+        let changes = diff(&before, &after);
+        let modified = &changes.modified[0];
+        assert_eq!(
+            modified.change("outcome_status.date").unwrap().before,
+            Value::Null
+        );
+        let updates = changes.outcome_updates();
+        assert_eq!(updates[0].previous, None);
+        assert!(updates[0].current.is_some());
+    }
+
+    #[test]
+    fn test_diff_stops_by_identifying_fields() {
+        let stop = |datetime: &str, outcome: &str| -> StopAndSearch {
+            serde_json::from_value(json!({
+                "type": "Person search",
+                "datetime": datetime,
+                "gender": "Male",
+                "outcome": outcome
+            }))
+            .unwrap()
+        };
+        let before = [
+            stop("2024-01-15T12:30:00+00:00", "A no further action disposal"),
+            stop("2024-01-15T12:30:00+00:00", "A no further action disposal"),
+        ];
+        let after = [
+            stop("2024-01-15T12:30:00+00:00", "Arrest"),
+            stop("2024-01-15T12:30:00+00:00", "A no further action disposal"),
+            stop("2024-01-20T09:00:00+00:00", "Arrest"),
+        ];
+        let changes = diff(&before, &after);
+        assert_eq!(changes.added, [&after[2]]);
+        assert!(changes.removed.is_empty());
+        assert_eq!(changes.modified.len(), 1);
+        assert_eq!(changes.modified[0].changes[0].field, "outcome");
+        assert_eq!(changes.modified[0].changes[0].after, json!("Arrest"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Schema drift tracking, available with the `extra-fields` feature.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{ExtraFields, join_path};
+
+/// Fields that differed from the client's models in responses from one
+/// endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointDrift {
+    /// Number of successful responses checked.
+    pub responses: u64,
+    /// Fields returned by the API that the models do not define, by dotted
+    /// path, with the number of records they appeared in.
+    pub new_fields: BTreeMap<String, u64>,
+    /// Fields the models define that the API left out, by dotted path, with
+    /// the number of records they were missing from.
+    pub missing_fields: BTreeMap<String, u64>,
+}
+
+impl EndpointDrift {
+    /// Returns `true` if no new or missing fields were seen.
+    pub fn is_empty(&self) -> bool {
+        self.new_fields.is_empty() && self.missing_fields.is_empty()
+    }
+}
+
+/// New and missing fields observed by a [`Client`](crate::Client), by
+/// endpoint. See [`Client::with_schema_drift`](crate::Client::with_schema_drift).
+///
+/// Paths are dotted field names relative to each record (e.g.
+/// `"location.street.name"`); items of arrays share their array's path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDriftReport {
+    /// Drift per endpoint, keyed by client method name.
+    pub endpoints: BTreeMap<&'static str, EndpointDrift>,
+}
+
+impl SchemaDriftReport {
+    /// Returns `true` if no endpoint saw new or missing fields.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.values().all(EndpointDrift::is_empty)
+    }
+}
+
+/// Collects a [`SchemaDriftReport`] across requests.
+#[derive(Default)]
+pub(crate) struct SchemaDrift {
+    report: Mutex<SchemaDriftReport>,
+}
+
+impl SchemaDrift {
+    /// Compares a decoded response with its raw body.
+    pub(crate) fn record<T: Serialize + ExtraFields>(
+        &self,
+        endpoint: &'static str,
+        value: &T,
+        body: &[u8],
+    ) {
+        let (Ok(raw), Ok(typed)) = (
+            serde_json::from_slice::<Value>(body),
+            serde_json::to_value(value),
+        ) else {
+            return;
+        };
+
+        let mut report = self.report.lock().unwrap();
+        let drift = report.endpoints.entry(endpoint).or_default();
+        drift.responses += 1;
+        value.visit_extra_fields("", &mut |path| {
+            *drift.new_fields.entry(path).or_default() += 1;
+        });
+        missing_fields("", &typed, &raw, &mut |path| {
+            *drift.missing_fields.entry(path).or_default() += 1;
+        });
+    }
+
+    pub(crate) fn report(&self) -> SchemaDriftReport {
+        self.report.lock().unwrap().clone()
+    }
+}
+
+/// Calls `visit` with the path of every object key present in `typed` (the
+/// re-serialized model) but absent from `raw` (the response body).
+fn missing_fields(prefix: &str, typed: &Value, raw: &Value, visit: &mut dyn FnMut(String)) {
+    match (typed, raw) {
+        (Value::Object(typed), Value::Object(raw)) => {
+            for (key, typed_value) in typed {
+                let path = join_path(prefix, key);
+                match raw.get(key) {
+                    Some(raw_value) => missing_fields(&path, typed_value, raw_value, visit),
+                    None => visit(path),
+                }
+            }
+        }
+        (Value::Array(typed), Value::Array(raw)) => {
+            for (typed_item, raw_item) in typed.iter().zip(raw) {
+                missing_fields(prefix, typed_item, raw_item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::Client;
+    use crate::models::{Area, LocationId};
+
+    #[tokio::test]
+    async fn test_extra_fields_and_drift_report() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crimes-street/all-crime"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "category": "burglary",
+                    "persistent_id": "",
+                    "location_subtype": "",
+                    "id": 1,
+                    "location": {
+                        "latitude": "52.6",
+                        "street": { "id": 2, "name": "On or near High Street", "postcode": "LE1" },
+                        "longitude": "-1.1"
+                    },
+                    "context": "",
+                    "month": "2024-01",
+                    "outcome_status": null,
+                    "severity": 3
+                }])),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_schema_drift(true);
+        let area = Area::LocationId(LocationId(2));
+        let crimes = client
+            .street_level_crimes("all-crime", &area, None)
+            .await
+            .unwrap();
+        client
+            .street_level_crimes("all-crime", &area, Some("2024-02"))
+            .await
+            .unwrap();
+
+        assert_eq!(crimes[0].extra["severity"], 3);
+        let street = &crimes[0].location.as_ref().unwrap().street;
+        assert_eq!(street.extra["postcode"], "LE1");
+        let json = serde_json::to_value(&crimes[0]).unwrap();
+        assert_eq!(json["location"]["street"]["postcode"], "LE1");
+
+        let report = client.schema_drift();
+        let drift = &report.endpoints["street_level_crimes"];
+        assert_eq!(drift.responses, 2);
+        assert_eq!(
+            drift.new_fields.iter().collect::<Vec<_>>(),
+            [
+                (&"location.street.postcode".to_string(), &2),
+                (&"severity".to_string(), &2)
+            ]
+        );
+        assert_eq!(
+            drift.missing_fields.keys().collect::<Vec<_>>(),
+            ["location_type"]
+        );
+        assert!(!report.is_empty());
+    }
+}
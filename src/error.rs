@@ -1,8 +1,44 @@
-#[derive(Debug, thiserror::Error)]
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
     #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[source] Arc<reqwest::Error>),
 
     #[error("API error (HTTP {status}): {body}")]
-    Api { status: u16, body: String },
+    Api {
+        status: u16,
+        body: String,
+        /// How long the server asked clients to wait before retrying, from
+        /// the `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+
+    #[error("failed to decode response: {0}")]
+    Decode(#[source] Arc<serde_json::Error>),
+}
+
+impl Error {
+    /// A short, stable name for the kind of error (`"http"`, `"api"` or
+    /// `"decode"`), suitable for use as a metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Http(_) => "http",
+            Self::Api { .. } => "api",
+            Self::Decode(_) => "decode",
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(Arc::new(err))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Decode(Arc::new(err))
+    }
 }
@@ -18,16 +18,53 @@
 //! # }
 //! ```
 
+pub mod analytics;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod catalogue;
 mod client;
+mod crawl;
+#[cfg(feature = "chrono")]
+pub mod datetime;
+pub mod diff;
+#[cfg(feature = "extra-fields")]
+mod drift;
 mod error;
+#[cfg(feature = "metrics")]
+mod metrics;
+pub mod middleware;
 pub mod models;
+mod observe;
+mod request;
+mod single_flight;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod sync;
+pub mod tiling;
+#[cfg(feature = "tracing")]
+mod trace;
+pub mod watch;
 
 pub use client::Client;
+pub use crawl::{
+    Checkpoint, Crawl, CrawlFailure, CrawlItem, CrawlNode, CrawlSummary, Crawler, FailedNode,
+};
+#[cfg(feature = "extra-fields")]
+pub use drift::{EndpointDrift, SchemaDriftReport};
 pub use error::Error;
+#[cfg(feature = "metrics")]
+pub use metrics::describe_metrics;
+pub use models::Extra;
+#[cfg(feature = "extra-fields")]
+pub use models::ExtraFields;
 pub use models::{
-    Area, ContactDetails, Coordinate, Crime, CrimeCategory, CrimeLastUpdated, CrimeOutcome,
-    CrimeOutcomes, EngagementMethod, Force, ForceDetail, LatLng, Link, LocateNeighbourhoodResult,
-    Location, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent, NeighbourhoodLocation,
-    NeighbourhoodPriority, Outcome, OutcomeCategory, OutcomeDetail, OutcomeObject, OutcomeStatus,
-    SeniorOfficer, StopAndSearch, StopAndSearchType, Street,
+    AgeRange, Area, BoundingBox, ContactDetails, Coordinate, Crime, CrimeAvailability,
+    CrimeCategory, CrimeLastUpdated, CrimeOutcome, CrimeOutcomes, EngagementMethod, Ethnicity,
+    Force, ForceDetail, ForceId, Gender, LatLng, Legislation, Link, LocateNeighbourhoodResult,
+    Location, LocationId, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent, NeighbourhoodId,
+    NeighbourhoodKey, NeighbourhoodLocation, NeighbourhoodPriority, ObjectOfSearch, Outcome,
+    OutcomeCategory, OutcomeDetail, OutcomeGroup, OutcomeObject, OutcomeStatus, ParseIdError,
+    ParseOutcomeCategoryError, PersistentId, SelfDefinedEthnicity, SeniorOfficer, StopAndSearch,
+    StopAndSearchType, StopOutcome, Street, Unrecognised, UnrecognisedValue,
 };
+pub use request::{Request, RequestArea, Response};
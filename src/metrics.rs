@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use ::metrics::{Unit, counter, describe_counter, describe_histogram, histogram};
+
+use crate::error::Error;
+use crate::request::Response;
+
+const REQUESTS: &str = "uk_police_api_requests_total";
+const DURATION: &str = "uk_police_api_request_duration_seconds";
+const RESPONSE_SIZE: &str = "uk_police_api_response_size_bytes";
+const ERRORS: &str = "uk_police_api_errors_total";
+const RATE_LIMITED: &str = "uk_police_api_rate_limited_total";
+const COALESCED: &str = "uk_police_api_coalesced_requests_total";
+const RETRIES: &str = "uk_police_api_retries_total";
+const CACHE: &str = "uk_police_api_cache_requests_total";
+const RATE_LIMIT_WAIT: &str = "uk_police_api_rate_limit_wait_seconds";
+
+/// Registers descriptions and units for every metric recorded by the client.
+///
+/// Call this once after installing a recorder so exporters can include help
+/// text. Metrics are recorded whether or not this is called.
+///
+/// Every metric is labeled by `endpoint`, the name of the [`Client`](crate::Client)
+/// method (e.g. `"street_level_crimes"`), never by URL.
+pub fn describe_metrics() {
+    describe_counter!(
+        REQUESTS,
+        "Requests by endpoint and HTTP status, or \"error\" if no status applies."
+    );
+    describe_histogram!(DURATION, Unit::Seconds, "Request latency by endpoint.");
+    describe_histogram!(
+        RESPONSE_SIZE,
+        Unit::Bytes,
+        "Size of successful response bodies by endpoint."
+    );
+    describe_counter!(
+        ERRORS,
+        "Failed requests, labeled by endpoint and error kind (http, api or decode)."
+    );
+    describe_counter!(
+        RATE_LIMITED,
+        "Requests rejected with HTTP 429 Too Many Requests, by endpoint."
+    );
+    describe_counter!(
+        COALESCED,
+        "Requests served by joining an identical in-flight request, by endpoint."
+    );
+    describe_counter!(
+        RETRIES,
+        "Retries made by the retry middleware, by endpoint."
+    );
+    describe_counter!(
+        CACHE,
+        "Cache middleware lookups, labeled by endpoint and result (hit or miss)."
+    );
+    describe_histogram!(
+        RATE_LIMIT_WAIT,
+        Unit::Seconds,
+        "Time spent waiting for the rate limit middleware, by endpoint."
+    );
+}
+
+/// Records a request that joined another caller's in-flight request.
+pub(crate) fn record_coalesced(endpoint: &'static str, coalesced: bool) {
+    if coalesced {
+        counter!(COALESCED, "endpoint" => endpoint).increment(1);
+    }
+}
+
+/// Records a retry made by the retry middleware.
+pub(crate) fn record_retry(endpoint: &'static str) {
+    counter!(RETRIES, "endpoint" => endpoint).increment(1);
+}
+
+/// Records a cache middleware lookup.
+pub(crate) fn record_cache(endpoint: &'static str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!(CACHE, "endpoint" => endpoint, "result" => result).increment(1);
+}
+
+/// Records time spent waiting for the rate limit middleware.
+pub(crate) fn record_rate_limit_wait(endpoint: &'static str, wait: Duration) {
+    histogram!(RATE_LIMIT_WAIT, "endpoint" => endpoint).record(wait.as_secs_f64());
+}
+
+/// Records the outcome of a request. The response size is only recorded by
+/// the caller that made the HTTP call, not by callers that joined it.
+pub(crate) fn record_result<T>(
+    endpoint: &'static str,
+    result: &Result<(Response, T), Error>,
+    latency: Duration,
+    coalesced: bool,
+) {
+    histogram!(DURATION, "endpoint" => endpoint).record(latency.as_secs_f64());
+
+    let status = match result {
+        Ok((response, _)) => {
+            if !coalesced {
+                histogram!(RESPONSE_SIZE, "endpoint" => endpoint)
+                    .record(response.body.len() as f64);
+            }
+            response.status.to_string()
+        }
+        Err(Error::Api { status, .. }) => status.to_string(),
+        Err(_) => "error".to_string(),
+    };
+    counter!(REQUESTS, "endpoint" => endpoint, "status" => status).increment(1);
+
+    if let Err(err) = result {
+        counter!(ERRORS, "endpoint" => endpoint, "kind" => err.kind()).increment(1);
+        if matches!(err, Error::Api { status: 429, .. }) {
+            counter!(RATE_LIMITED, "endpoint" => endpoint).increment(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::Client;
+
+    type Snapshot = Vec<(String, Vec<(String, String)>, DebugValue)>;
+
+    fn snapshot(snapshotter: &Snapshotter) -> Snapshot {
+        snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                let labels = key
+                    .labels()
+                    .map(|l| (l.key().to_string(), l.value().to_string()))
+                    .collect();
+                (key.name().to_string(), labels, value)
+            })
+            .collect()
+    }
+
+    fn counter(snapshot: &Snapshot, name: &str, labels: &[(&str, &str)]) -> Option<u64> {
+        snapshot.iter().find_map(|(key, key_labels, value)| {
+            let matches = key == name
+                && labels
+                    .iter()
+                    .all(|(k, v)| key_labels.iter().any(|(lk, lv)| lk == k && lv == v));
+            match value {
+                DebugValue::Counter(count) if matches => Some(*count),
+                _ => None,
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_metrics_recorded_per_endpoint() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": "kent", "name": "Kent Police" }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/forces/kent"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Rate limit exceeded"))
+            .mount(&server)
+            .await;
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let _guard = ::metrics::set_default_local_recorder(&recorder);
+
+        let client = Client::new().with_base_url(server.uri());
+        client.forces().await.unwrap();
+        client.force(&"kent".parse().unwrap()).await.unwrap_err();
+
+        let snapshot = snapshot(&snapshotter);
+
+        assert_eq!(
+            counter(
+                &snapshot,
+                "uk_police_api_requests_total",
+                &[("endpoint", "forces"), ("status", "200")]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            counter(
+                &snapshot,
+                "uk_police_api_errors_total",
+                &[("endpoint", "force"), ("kind", "api")]
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            counter(
+                &snapshot,
+                "uk_police_api_rate_limited_total",
+                &[("endpoint", "force")]
+            ),
+            Some(1)
+        );
+        assert!(snapshot.iter().any(|(name, labels, value)| {
+            name == "uk_police_api_response_size_bytes"
+                && labels.contains(&("endpoint".to_string(), "forces".to_string()))
+                && matches!(value, DebugValue::Histogram(sizes) if sizes.len() == 1)
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_coalesced_requests_record_response_size_once() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([]))
+                    .set_delay(std::time::Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let _guard = ::metrics::set_default_local_recorder(&recorder);
+
+        let client = Client::new().with_base_url(server.uri());
+        let (a, b) = tokio::join!(client.forces(), client.forces());
+        a.unwrap();
+        b.unwrap();
+
+        let snapshot = snapshot(&snapshotter);
+        assert_eq!(
+            counter(
+                &snapshot,
+                "uk_police_api_requests_total",
+                &[("endpoint", "forces"), ("status", "200")]
+            ),
+            Some(2)
+        );
+        assert!(snapshot.iter().any(|(name, _, value)| {
+            name == "uk_police_api_response_size_bytes"
+                && matches!(value, DebugValue::Histogram(sizes) if sizes.len() == 1)
+        }));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+
+use super::{Middleware, Next};
+use crate::error::Error;
+use crate::observe;
+use crate::request::{Request, Response};
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Caches successful responses in memory, keyed by URL.
+///
+/// Clones share the same cache, so keep a clone to read [`Cache::stats`]
+/// after adding the layer to a client. Errors are never cached.
+#[derive(Clone)]
+pub struct Cache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, Response)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Counters for a [`Cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Requests served from the cache.
+    pub hits: u64,
+    /// Requests passed on because no fresh entry was cached.
+    pub misses: u64,
+    /// Entries currently stored, including expired ones not yet evicted.
+    pub entries: usize,
+}
+
+impl Cache {
+    /// Creates a cache whose entries expire after `ttl`, holding up to 1024
+    /// responses.
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_capacity(ttl, DEFAULT_CAPACITY)
+    }
+
+    /// Creates a cache whose entries expire after `ttl`, holding up to
+    /// `capacity` responses. The oldest entry is evicted when full.
+    pub fn with_capacity(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                ttl,
+                capacity: capacity.max(1),
+                entries: Mutex::new(HashMap::new()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns hit and miss counts and the current number of entries.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            entries: self.inner.entries.lock().unwrap().len(),
+        }
+    }
+
+    /// Removes every cached response.
+    pub fn clear(&self) {
+        self.inner.entries.lock().unwrap().clear();
+    }
+
+    fn lookup(&self, url: &str) -> Option<Response> {
+        let entries = self.inner.entries.lock().unwrap();
+        entries
+            .get(url)
+            .filter(|(stored, _)| stored.elapsed() < self.inner.ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    fn store(&self, url: String, response: Response) {
+        let mut entries = self.inner.entries.lock().unwrap();
+        if entries.len() >= self.inner.capacity && !entries.contains_key(&url) {
+            let ttl = self.inner.ttl;
+            entries.retain(|_, (stored, _)| stored.elapsed() < ttl);
+            if entries.len() >= self.inner.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (stored, _))| *stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(url, (Instant::now(), response));
+    }
+}
+
+impl fmt::Debug for Cache {
+    /// Shows the settings and counters, not the cached responses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = self.stats();
+        f.debug_struct("Cache")
+            .field("ttl", &self.inner.ttl)
+            .field("capacity", &self.inner.capacity)
+            .field("entries", &stats.entries)
+            .field("hits", &stats.hits)
+            .field("misses", &stats.misses)
+            .finish()
+    }
+}
+
+impl Middleware for Cache {
+    fn handle<'a>(
+        &'a self,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move {
+            if let Some(response) = self.lookup(&request.url) {
+                self.inner.hits.fetch_add(1, Ordering::Relaxed);
+                observe::cache(request.endpoint, true);
+                return Ok(response);
+            }
+            self.inner.misses.fetch_add(1, Ordering::Relaxed);
+            observe::cache(request.endpoint, false);
+
+            let url = request.url.clone();
+            let response = next.run(request).await?;
+            self.store(url, response.clone());
+            Ok(response)
+        })
+    }
+}
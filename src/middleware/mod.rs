@@ -0,0 +1,377 @@
+//! Composable layers around the HTTP call made for every request.
+//!
+//! Each [`Middleware`] receives the [`Request`] and a [`Next`] handle for the
+//! rest of the chain. It can modify the request (e.g. add headers or rewrite
+//! the URL to an internal proxy), inspect or replace the [`Response`], call
+//! `next` several times, or return a response without calling it at all.
+//! Layers run in the order they are added to the client, so the first layer
+//! added is the outermost.
+//!
+//! The crate ships three built-in layers: [`Retry`], [`RateLimit`] and
+//! [`Cache`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures_util::future::BoxFuture;
+//! use reqwest::header::HeaderValue;
+//! use uk_police_api::middleware::{Middleware, Next, RateLimit, Retry};
+//! use uk_police_api::{Client, Error, Request, Response};
+//!
+//! struct AuditHeader;
+//!
+//! impl Middleware for AuditHeader {
+//!     fn handle<'a>(
+//!         &'a self,
+//!         mut request: Request,
+//!         next: Next<'a>,
+//!     ) -> BoxFuture<'a, Result<Response, Error>> {
+//!         request
+//!             .headers
+//!             .insert("x-audit", HeaderValue::from_static("nightly-snapshot"));
+//!         Box::pin(next.run(request))
+//!     }
+//! }
+//!
+//! let client = Client::new()
+//!     .with_middleware(AuditHeader)
+//!     .with_middleware(Retry::new(3))
+//!     .with_middleware(RateLimit::default());
+//! ```
+
+mod cache;
+mod rate_limit;
+mod retry;
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use futures_util::future::BoxFuture;
+use reqwest::header::RETRY_AFTER;
+
+use crate::error::Error;
+use crate::request::{Request, Response};
+
+pub use cache::{Cache, CacheStats};
+pub use rate_limit::RateLimit;
+pub use retry::Retry;
+
+/// A layer in the request chain. See the [module documentation](self).
+pub trait Middleware: Send + Sync + 'static {
+    /// Handles a request, usually by passing it on with [`Next::run`].
+    fn handle<'a>(
+        &'a self,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response, Error>>;
+}
+
+/// The remainder of the middleware chain, ending in the HTTP call.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    http: &'a reqwest::Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(http: &'a reqwest::Client, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { http, middleware }
+    }
+
+    /// Passes the request to the next layer, or sends it if there are no
+    /// layers left.
+    pub async fn run(self, request: Request) -> Result<Response, Error> {
+        match self.middleware.split_first() {
+            Some((layer, rest)) => layer.handle(request, Next::new(self.http, rest)).await,
+            None => send(self.http, request).await,
+        }
+    }
+}
+
+async fn send(http: &reqwest::Client, request: Request) -> Result<Response, Error> {
+    let response = http
+        .get(&request.url)
+        .headers(request.headers)
+        .send()
+        .await?;
+    let status = response.status().as_u16();
+    if !response.status().is_success() {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::Api {
+            status,
+            body,
+            retry_after,
+        });
+    }
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    Ok(Response {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Parses a `Retry-After` value, given either as a number of seconds or as
+/// an HTTP date. Dates in the past mean no wait.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value.trim()).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use reqwest::header::HeaderValue;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::Client;
+
+    struct AuditHeader;
+
+    impl Middleware for AuditHeader {
+        fn handle<'a>(
+            &'a self,
+            mut request: Request,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<Response, Error>> {
+            request
+                .headers
+                .insert("x-audit", HeaderValue::from_static("test"));
+            Box::pin(next.run(request))
+        }
+    }
+
+    struct ShortCircuit;
+
+    impl Middleware for ShortCircuit {
+        fn handle<'a>(
+            &'a self,
+            request: Request,
+            _next: Next<'a>,
+        ) -> BoxFuture<'a, Result<Response, Error>> {
+            Box::pin(async move {
+                assert_eq!(request.endpoint, "force");
+                assert_eq!(request.force.as_deref(), Some("kent"));
+                Ok(Response::new(
+                    200,
+                    r#"{"id":"kent","name":"Kent Police","description":null,"url":null,"telephone":null,"engagement_methods":[]}"#,
+                ))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_modifies_request() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .and(header("x-audit", "test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_middleware(AuditHeader);
+        assert!(client.forces().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_short_circuits() {
+        let client = Client::new()
+            .with_base_url("http://127.0.0.1:9")
+            .with_middleware(ShortCircuit);
+        let force = client.force(&"kent".parse().unwrap()).await.unwrap();
+        assert_eq!(force.name, "Kent Police");
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_server_errors() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_middleware(Retry::new(2).base_delay(Duration::from_millis(1)));
+        assert!(client.forces().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_on_client_errors() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces/nonexistent"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_middleware(Retry::new(3).base_delay(Duration::from_millis(1)));
+        let err = client
+            .force(&"nonexistent".parse().unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Api { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_retry_honours_retry_after() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The backoff alone would wait a minute.
+        let client = Client::new().with_base_url(server.uri()).with_middleware(
+            Retry::new(1)
+                .base_delay(Duration::from_secs(60))
+                .max_delay(Duration::from_secs(60)),
+        );
+        let started = Instant::now();
+        assert!(client.forces().await.unwrap().is_empty());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_when_retry_after_exceeds_max_delay() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "120"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_middleware(Retry::new(3).max_delay(Duration::from_secs(1)));
+        let err = client.forces().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Api {
+                status: 503,
+                retry_after: Some(wait),
+                ..
+            } if wait == Duration::from_secs(120)
+        ));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(90));
+        let wait = parse_retry_after(&later).unwrap();
+        assert!(wait > Duration::from_secs(80) && wait <= Duration::from_secs(90));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_cache_debug_hides_responses() {
+        let cache = Cache::new(Duration::from_secs(60));
+        let debug = format!("{cache:?}");
+        assert!(debug.contains("ttl: 60s"));
+        assert!(debug.contains("entries: 0"));
+        assert!(!debug.contains("Response"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_serves_repeat_requests() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crime-categories"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "url": "burglary", "name": "Burglary" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = Cache::new(Duration::from_secs(60));
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_middleware(cache.clone());
+        client.crime_categories(None).await.unwrap();
+        let categories = client.crime_categories(None).await.unwrap();
+
+        assert_eq!(categories[0].url, "burglary");
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                entries: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_requests() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/forces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_request_coalescing(false)
+            .with_middleware(RateLimit::new(20, 1));
+        let started = Instant::now();
+        for _ in 0..3 {
+            client.forces().await.unwrap();
+        }
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+
+use super::{Middleware, Next};
+use crate::error::Error;
+use crate::observe;
+use crate::request::{Request, Response};
+
+/// Limits the rate of outgoing requests with a token bucket.
+///
+/// The limit is shared by every client the layer (or a clone of it) is added
+/// to. Requests that exceed it wait their turn rather than failing.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    rate: f64,
+    burst: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimit {
+    /// Allows `requests_per_second` on average, with bursts of up to `burst`.
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: f64::from(requests_per_second.max(1)),
+            burst,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Reserves a token and returns how long to wait before using it.
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+}
+
+impl Default for RateLimit {
+    /// The limit documented by the police API: 15 requests per second with
+    /// bursts of up to 30.
+    fn default() -> Self {
+        Self::new(15, 30)
+    }
+}
+
+impl Middleware for RateLimit {
+    fn handle<'a>(
+        &'a self,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move {
+            let wait = self.reserve();
+            if !wait.is_zero() {
+                observe::rate_limit_wait(request.endpoint, wait);
+                tokio::time::sleep(wait).await;
+            }
+            next.run(request).await
+        })
+    }
+}
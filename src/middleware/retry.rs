@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+
+use super::{Middleware, Next};
+use crate::error::Error;
+use crate::observe;
+use crate::request::{Request, Response};
+
+/// Retries requests that fail with a transient error.
+///
+/// Connection failures, timeouts, `429 Too Many Requests` and `5xx` responses
+/// are retried with exponential backoff. Other errors are returned
+/// immediately.
+///
+/// When the response has a `Retry-After` header, the retry waits that long
+/// instead. If the server asks for a longer wait than the maximum delay, the
+/// error is returned rather than retrying too early.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Retry {
+    /// Creates a retry layer that makes up to `max_retries` extra attempts.
+    ///
+    /// The delay starts at 500ms and doubles after each attempt, up to 30s.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay between retries.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay)
+    }
+
+    fn is_transient(err: &Error) -> bool {
+        match err {
+            Error::Http(err) => err.is_connect() || err.is_timeout() || err.is_request(),
+            Error::Api { status, .. } => *status == 429 || *status >= 500,
+            Error::Decode(_) => false,
+        }
+    }
+}
+
+impl Middleware for Retry {
+    fn handle<'a>(
+        &'a self,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                match next.run(request.clone()).await {
+                    Err(err) if attempt < self.max_retries && Self::is_transient(&err) => {
+                        let delay = match &err {
+                            Error::Api {
+                                retry_after: Some(wait),
+                                ..
+                            } => *wait,
+                            _ => self.delay(attempt + 1),
+                        };
+                        if delay > self.max_delay {
+                            return Err(err);
+                        }
+                        attempt += 1;
+                        observe::retry(request.endpoint, attempt);
+                        tokio::time::sleep(delay).await;
+                    }
+                    result => return result,
+                }
+            }
+        })
+    }
+}
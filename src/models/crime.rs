@@ -1,7 +1,11 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+use super::{ForceId, LocationId, PersistentId};
+
 /// A latitude/longitude pair.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Coordinate {
     pub lat: f64,
     pub lng: f64,
@@ -15,7 +19,71 @@ pub enum Area {
     /// Search within a custom polygon defined by a list of coordinates.
     Custom(Vec<Coordinate>),
     /// Search at a specific location ID (returned by other API methods).
-    LocationId(u64),
+    LocationId(LocationId),
+}
+
+/// A rectangle of latitude and longitude, inclusive of its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BoundingBox {
+    /// Southern edge (minimum latitude).
+    pub south: f64,
+    /// Western edge (minimum longitude).
+    pub west: f64,
+    /// Northern edge (maximum latitude).
+    pub north: f64,
+    /// Eastern edge (maximum longitude).
+    pub east: f64,
+}
+
+impl BoundingBox {
+    /// Returns the smallest box containing every point, or `None` if there
+    /// are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Coordinate>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self {
+            south: first.lat,
+            west: first.lng,
+            north: first.lat,
+            east: first.lng,
+        };
+        for point in points {
+            bounds.south = bounds.south.min(point.lat);
+            bounds.north = bounds.north.max(point.lat);
+            bounds.west = bounds.west.min(point.lng);
+            bounds.east = bounds.east.max(point.lng);
+        }
+        Some(bounds)
+    }
+
+    /// Returns `true` if the point lies within the box.
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        (self.south..=self.north).contains(&lat) && (self.west..=self.east).contains(&lng)
+    }
+}
+
+impl From<BoundingBox> for Area {
+    /// A custom area with the box's four corners.
+    fn from(bounds: BoundingBox) -> Self {
+        Area::Custom(vec![
+            Coordinate {
+                lat: bounds.south,
+                lng: bounds.west,
+            },
+            Coordinate {
+                lat: bounds.north,
+                lng: bounds.west,
+            },
+            Coordinate {
+                lat: bounds.north,
+                lng: bounds.east,
+            },
+            Coordinate {
+                lat: bounds.south,
+                lng: bounds.east,
+            },
+        ])
+    }
 }
 
 /// A category of crime (e.g. "Burglary", "Drugs").
@@ -25,6 +93,11 @@ pub struct CrimeCategory {
     pub url: String,
     /// Human-readable category name.
     pub name: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// The date when crime data was last updated
@@ -33,6 +106,26 @@ pub struct CrimeLastUpdated {
     /// Month of the latest crime data in ISO date format.
     /// The day is irrelevant and is only there to keep a standard formatted date.
     pub date: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
+}
+
+/// A month for which street-level crime data is available.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrimeAvailability {
+    /// Month of the data (format: `YYYY-MM`).
+    pub date: String,
+    /// Forces that have published stop and search data for this month.
+    #[serde(rename = "stop-and-search")]
+    pub stop_and_search: Vec<ForceId>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A crime record.
@@ -40,8 +133,10 @@ pub struct CrimeLastUpdated {
 pub struct Crime {
     /// Crime category (e.g. "anti-social-behaviour", "burglary").
     pub category: String,
-    /// 64-character unique identifier for the crime.
-    pub persistent_id: String,
+    /// 64-character unique identifier for the crime. `None` where the API
+    /// returns an empty string, e.g. for anti-social behaviour.
+    #[serde(default, with = "super::ids::optional_persistent_id")]
+    pub persistent_id: Option<PersistentId>,
     /// For BTP locations, the type of location at which this crime was recorded.
     pub location_subtype: String,
     /// API identifier for the crime. Not a police identifier.
@@ -56,6 +151,11 @@ pub struct Crime {
     pub location_type: Option<String>,
     /// The latest recorded outcome for the crime, if available.
     pub outcome_status: Option<OutcomeStatus>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Approximate location of a crime.
@@ -67,15 +167,36 @@ pub struct Location {
     pub street: Street,
     /// Longitude.
     pub longitude: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl Location {
+    /// Parses the latitude and longitude, or returns `None` if either is not
+    /// a number.
+    pub fn coordinate(&self) -> Option<Coordinate> {
+        Some(Coordinate {
+            lat: self.latitude.parse().ok()?,
+            lng: self.longitude.parse().ok()?,
+        })
+    }
 }
 
 /// A street associated with a crime location.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Street {
-    /// Unique identifier for the street.
-    pub id: u64,
+    /// Unique identifier for the street, used as a location ID.
+    pub id: LocationId,
     /// Name of the location. This is only an approximation.
     pub name: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// The latest outcome of a crime.
@@ -85,130 +206,233 @@ pub struct OutcomeStatus {
     pub category: OutcomeCategory,
     /// Date of the outcome (format: `YYYY-MM`).
     pub date: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
-/// Outcome category. Deserializes from both kebab-case codes (e.g. "local-resolution")
-/// and full names (e.g. "Local resolution") as different API endpoints use different formats.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum OutcomeCategory {
-    #[serde(rename = "awaiting-court-result", alias = "Awaiting court outcome")]
-    AwaitingCourtResult,
-    #[serde(
-        rename = "court-result-unavailable",
-        alias = "Court result unavailable"
-    )]
-    CourtResultUnavailable,
-    #[serde(rename = "unable-to-proceed", alias = "Court case unable to proceed")]
-    UnableToProceed,
-    #[serde(rename = "local-resolution", alias = "Local resolution")]
-    LocalResolution,
-    #[serde(
-        rename = "no-further-action",
-        alias = "Investigation complete; no suspect identified"
-    )]
-    NoFurtherAction,
-    #[serde(
-        rename = "deprived-of-property",
-        alias = "Offender deprived of property"
-    )]
-    DeprivedOfProperty,
-    #[serde(rename = "fined", alias = "Offender fined")]
-    Fined,
-    #[serde(
-        rename = "absolute-discharge",
-        alias = "Offender given absolute discharge"
-    )]
-    AbsoluteDischarge,
-    #[serde(rename = "cautioned", alias = "Offender given a caution")]
-    Cautioned,
-    #[serde(
-        rename = "drugs-possession-warning",
-        alias = "Offender given a drugs possession warning"
-    )]
-    DrugsPossessionWarning,
-    #[serde(
-        rename = "penalty-notice-issued",
-        alias = "Offender given a penalty notice",
-        alias = "Offender given penalty notice"
-    )]
-    PenaltyNoticeIssued,
-    #[serde(
-        rename = "community-penalty",
-        alias = "Offender given community sentence"
-    )]
-    CommunityPenalty,
-    #[serde(
-        rename = "conditional-discharge",
-        alias = "Offender given conditional discharge"
-    )]
-    ConditionalDischarge,
-    #[serde(
-        rename = "suspended-sentence",
-        alias = "Offender given suspended prison sentence"
-    )]
-    SuspendedSentence,
-    #[serde(rename = "imprisoned", alias = "Offender sent to prison")]
-    Imprisoned,
-    #[serde(
-        rename = "other-court-disposal",
-        alias = "Offender otherwise dealt with"
-    )]
-    OtherCourtDisposal,
-    #[serde(
-        rename = "compensation",
-        alias = "Offender ordered to pay compensation"
-    )]
-    Compensation,
-    #[serde(
-        rename = "sentenced-in-another-case",
-        alias = "Suspect charged as part of another case"
-    )]
-    SentencedInAnotherCase,
-    #[serde(rename = "charged", alias = "Suspect charged")]
-    Charged,
-    #[serde(rename = "not-guilty", alias = "Defendant found not guilty")]
-    NotGuilty,
-    #[serde(
-        rename = "sent-to-crown-court",
-        alias = "Defendant sent to Crown Court"
-    )]
-    SentToCrownCourt,
-    #[serde(rename = "unable-to-prosecute", alias = "Unable to prosecute suspect")]
-    UnableToProsecute,
-    #[serde(
-        rename = "formal-action-not-in-public-interest",
-        alias = "Formal action is not in the public interest"
-    )]
-    FormalActionNotInPublicInterest,
-    #[serde(
-        rename = "action-taken-by-another-organisation",
-        alias = "Action to be taken by another organisation"
-    )]
-    ActionTakenByAnotherOrganisation,
-    #[serde(
-        rename = "further-investigation-not-in-public-interest",
-        alias = "Further investigation is not in the public interest"
-    )]
-    FurtherInvestigationNotInPublicInterest,
-    #[serde(
-        rename = "further-action-not-in-public-interest",
-        alias = "Further action is not in the public interest"
-    )]
-    FurtherActionNotInPublicInterest,
-    #[serde(rename = "under-investigation", alias = "Under investigation")]
-    UnderInvestigation,
-    #[serde(
-        rename = "status-update-unavailable",
-        alias = "Status update unavailable"
-    )]
-    StatusUpdateUnavailable,
-    /// Any outcome category not recognised by this client.
+string_enum! {
+    /// Outcome category. Deserializes from both kebab-case codes (e.g. "local-resolution")
+    /// and full names (e.g. "Local resolution") as different API endpoints use different
+    /// formats, and serializes to the code.
     ///
-    /// The police API occasionally adds or rewords outcome categories. This
-    /// catch-all keeps deserialization from failing on a value the client does
-    /// not yet recognise.
-    #[serde(other)]
-    Unknown,
+    /// The police API occasionally adds or rewords outcome categories; those
+    /// are kept as [`OutcomeCategory::Unknown`] with the value as returned.
+    pub enum OutcomeCategory {
+        AwaitingCourtResult => "awaiting-court-result" | "Awaiting court outcome",
+        CourtResultUnavailable => "court-result-unavailable" | "Court result unavailable",
+        UnableToProceed => "unable-to-proceed" | "Court case unable to proceed",
+        LocalResolution => "local-resolution" | "Local resolution",
+        NoFurtherAction => "no-further-action" | "Investigation complete; no suspect identified",
+        DeprivedOfProperty => "deprived-of-property" | "Offender deprived of property",
+        Fined => "fined" | "Offender fined",
+        AbsoluteDischarge => "absolute-discharge" | "Offender given absolute discharge",
+        Cautioned => "cautioned" | "Offender given a caution",
+        DrugsPossessionWarning => "drugs-possession-warning"
+            | "Offender given a drugs possession warning",
+        PenaltyNoticeIssued => "penalty-notice-issued"
+            | "Offender given a penalty notice"
+            | "Offender given penalty notice",
+        CommunityPenalty => "community-penalty" | "Offender given community sentence",
+        ConditionalDischarge => "conditional-discharge" | "Offender given conditional discharge",
+        SuspendedSentence => "suspended-sentence" | "Offender given suspended prison sentence",
+        Imprisoned => "imprisoned" | "Offender sent to prison",
+        OtherCourtDisposal => "other-court-disposal" | "Offender otherwise dealt with",
+        Compensation => "compensation" | "Offender ordered to pay compensation",
+        SentencedInAnotherCase => "sentenced-in-another-case"
+            | "Suspect charged as part of another case",
+        Charged => "charged" | "Suspect charged",
+        NotGuilty => "not-guilty" | "Defendant found not guilty",
+        SentToCrownCourt => "sent-to-crown-court" | "Defendant sent to Crown Court",
+        UnableToProsecute => "unable-to-prosecute" | "Unable to prosecute suspect",
+        FormalActionNotInPublicInterest => "formal-action-not-in-public-interest"
+            | "Formal action is not in the public interest",
+        ActionTakenByAnotherOrganisation => "action-taken-by-another-organisation"
+            | "Action to be taken by another organisation",
+        FurtherInvestigationNotInPublicInterest => "further-investigation-not-in-public-interest"
+            | "Further investigation is not in the public interest",
+        FurtherActionNotInPublicInterest => "further-action-not-in-public-interest"
+            | "Further action is not in the public interest",
+        UnderInvestigation => "under-investigation" | "Under investigation",
+        StatusUpdateUnavailable => "status-update-unavailable" | "Status update unavailable",
+    }
+}
+
+impl OutcomeCategory {
+    /// Every recognised category, i.e. all but [`OutcomeCategory::Unknown`].
+    pub const ALL: [Self; 28] = [
+        Self::AwaitingCourtResult,
+        Self::CourtResultUnavailable,
+        Self::UnableToProceed,
+        Self::LocalResolution,
+        Self::NoFurtherAction,
+        Self::DeprivedOfProperty,
+        Self::Fined,
+        Self::AbsoluteDischarge,
+        Self::Cautioned,
+        Self::DrugsPossessionWarning,
+        Self::PenaltyNoticeIssued,
+        Self::CommunityPenalty,
+        Self::ConditionalDischarge,
+        Self::SuspendedSentence,
+        Self::Imprisoned,
+        Self::OtherCourtDisposal,
+        Self::Compensation,
+        Self::SentencedInAnotherCase,
+        Self::Charged,
+        Self::NotGuilty,
+        Self::SentToCrownCourt,
+        Self::UnableToProsecute,
+        Self::FormalActionNotInPublicInterest,
+        Self::ActionTakenByAnotherOrganisation,
+        Self::FurtherInvestigationNotInPublicInterest,
+        Self::FurtherActionNotInPublicInterest,
+        Self::UnderInvestigation,
+        Self::StatusUpdateUnavailable,
+    ];
+
+    /// Returns the kebab-case code (e.g. "local-resolution"), or the raw value
+    /// for an unrecognised category.
+    pub fn code(&self) -> &str {
+        self.as_str()
+    }
+
+    /// Returns the human-readable label (e.g. "Local resolution"), or the raw
+    /// value for an unrecognised category.
+    pub fn label(&self) -> &str {
+        match self {
+            Self::AwaitingCourtResult => "Awaiting court outcome",
+            Self::CourtResultUnavailable => "Court result unavailable",
+            Self::UnableToProceed => "Court case unable to proceed",
+            Self::LocalResolution => "Local resolution",
+            Self::NoFurtherAction => "Investigation complete; no suspect identified",
+            Self::DeprivedOfProperty => "Offender deprived of property",
+            Self::Fined => "Offender fined",
+            Self::AbsoluteDischarge => "Offender given absolute discharge",
+            Self::Cautioned => "Offender given a caution",
+            Self::DrugsPossessionWarning => "Offender given a drugs possession warning",
+            Self::PenaltyNoticeIssued => "Offender given a penalty notice",
+            Self::CommunityPenalty => "Offender given community sentence",
+            Self::ConditionalDischarge => "Offender given conditional discharge",
+            Self::SuspendedSentence => "Offender given suspended prison sentence",
+            Self::Imprisoned => "Offender sent to prison",
+            Self::OtherCourtDisposal => "Offender otherwise dealt with",
+            Self::Compensation => "Offender ordered to pay compensation",
+            Self::SentencedInAnotherCase => "Suspect charged as part of another case",
+            Self::Charged => "Suspect charged",
+            Self::NotGuilty => "Defendant found not guilty",
+            Self::SentToCrownCourt => "Defendant sent to Crown Court",
+            Self::UnableToProsecute => "Unable to prosecute suspect",
+            Self::FormalActionNotInPublicInterest => "Formal action is not in the public interest",
+            Self::ActionTakenByAnotherOrganisation => "Action to be taken by another organisation",
+            Self::FurtherInvestigationNotInPublicInterest => {
+                "Further investigation is not in the public interest"
+            }
+            Self::FurtherActionNotInPublicInterest => {
+                "Further action is not in the public interest"
+            }
+            Self::UnderInvestigation => "Under investigation",
+            Self::StatusUpdateUnavailable => "Status update unavailable",
+            Self::Unknown(value) => value,
+        }
+    }
+
+    /// Returns the coarse group the category belongs to.
+    pub fn group(&self) -> OutcomeGroup {
+        match self {
+            Self::UnderInvestigation | Self::StatusUpdateUnavailable | Self::Unknown(_) => {
+                OutcomeGroup::InvestigationOngoing
+            }
+            Self::NoFurtherAction
+            | Self::UnableToProsecute
+            | Self::FormalActionNotInPublicInterest
+            | Self::ActionTakenByAnotherOrganisation
+            | Self::FurtherInvestigationNotInPublicInterest
+            | Self::FurtherActionNotInPublicInterest => OutcomeGroup::NoFurtherAction,
+            Self::LocalResolution
+            | Self::Cautioned
+            | Self::DrugsPossessionWarning
+            | Self::PenaltyNoticeIssued => OutcomeGroup::OutOfCourtDisposal,
+            Self::AwaitingCourtResult
+            | Self::CourtResultUnavailable
+            | Self::UnableToProceed
+            | Self::SentencedInAnotherCase
+            | Self::Charged
+            | Self::SentToCrownCourt => OutcomeGroup::CourtOutcome,
+            Self::DeprivedOfProperty
+            | Self::Fined
+            | Self::AbsoluteDischarge
+            | Self::CommunityPenalty
+            | Self::ConditionalDischarge
+            | Self::SuspendedSentence
+            | Self::Imprisoned
+            | Self::OtherCourtDisposal
+            | Self::Compensation => OutcomeGroup::Conviction,
+            Self::NotGuilty => OutcomeGroup::Acquittal,
+        }
+    }
+
+    /// Returns `true` if no later outcome is expected, i.e. the case is not
+    /// still under investigation or awaiting a court decision.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(
+            self,
+            Self::UnderInvestigation
+                | Self::StatusUpdateUnavailable
+                | Self::AwaitingCourtResult
+                | Self::Charged
+                | Self::SentToCrownCourt
+                | Self::Unknown(_)
+        )
+    }
+
+    /// Returns `true` if the category implies a suspect was identified.
+    pub fn is_suspect_identified(&self) -> bool {
+        !matches!(
+            self,
+            Self::UnderInvestigation
+                | Self::StatusUpdateUnavailable
+                | Self::NoFurtherAction
+                | Self::Unknown(_)
+        )
+    }
+}
+
+impl FromStr for OutcomeCategory {
+    type Err = ParseOutcomeCategoryError;
+
+    /// Parses a kebab-case code or a full name, ignoring case and surrounding
+    /// whitespace. Matches exactly the values [`OutcomeCategory::from`]
+    /// recognises, but fails instead of returning
+    /// [`OutcomeCategory::Unknown`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_known(s).ok_or_else(|| ParseOutcomeCategoryError(s.trim().to_string()))
+    }
+}
+
+/// Error returned when parsing an unrecognised [`OutcomeCategory`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unrecognised outcome category: {0:?}")]
+pub struct ParseOutcomeCategoryError(pub String);
+
+/// Coarse grouping of [`OutcomeCategory`] values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutcomeGroup {
+    /// Still under investigation, or no status available.
+    InvestigationOngoing,
+    /// Closed without action, with or without a suspect identified.
+    NoFurtherAction,
+    /// Caution, penalty notice, drugs warning or local resolution.
+    OutOfCourtDisposal,
+    /// Charged or before the courts, without a verdict recorded.
+    CourtOutcome,
+    /// Convicted and sentenced.
+    Conviction,
+    /// Found not guilty.
+    Acquittal,
 }
 
 /// Outcome category detail object returned by outcome endpoints.
@@ -219,6 +443,11 @@ pub struct OutcomeDetail {
     pub code: OutcomeCategory,
     /// Human-readable category name.
     pub name: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A street-level outcome record.
@@ -232,6 +461,11 @@ pub struct Outcome {
     pub person_id: Option<String>,
     /// The crime this outcome relates to.
     pub crime: Crime,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// All outcomes for a specific crime.
@@ -241,6 +475,11 @@ pub struct CrimeOutcomes {
     pub crime: Crime,
     /// List of outcomes for this crime.
     pub outcomes: Vec<CrimeOutcome>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// An individual outcome within a [`CrimeOutcomes`] response.
@@ -252,4 +491,9 @@ pub struct CrimeOutcome {
     pub date: String,
     /// Identifier for the suspect/offender, where available.
     pub person_id: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
@@ -0,0 +1,96 @@
+use super::{
+    ContactDetails, Crime, CrimeAvailability, CrimeCategory, CrimeLastUpdated, CrimeOutcome,
+    CrimeOutcomes, EngagementMethod, Force, ForceDetail, LatLng, Link, LocateNeighbourhoodResult,
+    Location, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent, NeighbourhoodLocation,
+    NeighbourhoodPriority, Outcome, OutcomeDetail, OutcomeObject, OutcomeStatus, SeniorOfficer,
+    StopAndSearch, Street,
+};
+
+/// Walks the `extra` maps of a model and every model nested inside it.
+///
+/// Implemented for every model and for `Vec`s and `Option`s of them.
+pub trait ExtraFields {
+    /// Calls `visit` with the dotted path (e.g. `"location.street.postcode"`)
+    /// of every unrecognised field. `prefix` is prepended to each path; pass
+    /// `""` for the top level. Items of arrays share their array's path.
+    fn visit_extra_fields(&self, prefix: &str, visit: &mut dyn FnMut(String));
+
+    /// Returns the paths of every unrecognised field, in visiting order.
+    fn extra_field_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.visit_extra_fields("", &mut |path| paths.push(path));
+        paths
+    }
+}
+
+/// Joins a field name onto a dotted path.
+pub(crate) fn join_path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{prefix}.{field}")
+    }
+}
+
+impl<T: ExtraFields> ExtraFields for Vec<T> {
+    fn visit_extra_fields(&self, prefix: &str, visit: &mut dyn FnMut(String)) {
+        for item in self {
+            item.visit_extra_fields(prefix, visit);
+        }
+    }
+}
+
+impl<T: ExtraFields> ExtraFields for Option<T> {
+    fn visit_extra_fields(&self, prefix: &str, visit: &mut dyn FnMut(String)) {
+        if let Some(value) = self {
+            value.visit_extra_fields(prefix, visit);
+        }
+    }
+}
+
+/// Implements [`ExtraFields`] for models, visiting their own `extra` map and
+/// then each listed nested model field.
+macro_rules! extra_fields {
+    ($($ty:ty $({ $($field:ident),* })?;)*) => {
+        $(impl ExtraFields for $ty {
+            fn visit_extra_fields(&self, prefix: &str, visit: &mut dyn FnMut(String)) {
+                for key in self.extra.keys() {
+                    visit(join_path(prefix, key));
+                }
+                $($(
+                    self.$field
+                        .visit_extra_fields(&join_path(prefix, stringify!($field)), visit);
+                )*)?
+            }
+        })*
+    };
+}
+
+extra_fields! {
+    ContactDetails;
+    Crime { location, outcome_status };
+    CrimeAvailability;
+    CrimeCategory;
+    CrimeLastUpdated;
+    CrimeOutcome { category };
+    CrimeOutcomes { crime, outcomes };
+    EngagementMethod;
+    Force;
+    ForceDetail { engagement_methods };
+    LatLng;
+    Link;
+    LocateNeighbourhoodResult;
+    Location { street };
+    Neighbourhood;
+    NeighbourhoodDetail { contact_details, centre, links, locations };
+    NeighbourhoodEvent { contact_details };
+    NeighbourhoodLocation;
+    NeighbourhoodPriority;
+    Outcome { category, crime };
+    OutcomeDetail;
+    OutcomeObject;
+    OutcomeStatus;
+    SeniorOfficer { contact_details };
+    StopAndSearch { location, outcome_object };
+    Street;
+}
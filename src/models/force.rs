@@ -1,19 +1,26 @@
 use serde::{Deserialize, Serialize};
 
+use super::ForceId;
+
 /// A summary of a police force.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Force {
     /// Unique force identifier.
-    pub id: String,
+    pub id: ForceId,
     /// Force name.
     pub name: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Detailed information about a specific police force.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ForceDetail {
     /// Unique force identifier.
-    pub id: String,
+    pub id: ForceId,
     /// Force name.
     pub name: String,
     /// Description of the force.
@@ -24,6 +31,11 @@ pub struct ForceDetail {
     pub telephone: Option<String>,
     /// Ways to keep informed about the force.
     pub engagement_methods: Vec<EngagementMethod>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A senior officer of a police force.
@@ -37,6 +49,11 @@ pub struct SeniorOfficer {
     pub bio: Option<String>,
     /// Contact details for the officer.
     pub contact_details: ContactDetails,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Contact details for a senior officer.
@@ -61,6 +78,11 @@ pub struct ContactDetails {
     pub e_messaging: Option<String>,
     pub blog: Option<String>,
     pub rss: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A way to engage with a police force (e.g. Twitter, Facebook, website).
@@ -75,4 +97,9 @@ pub struct EngagementMethod {
     pub description: Option<String>,
     /// Method website URL.
     pub url: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Error returned when a string is not a valid identifier.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid {kind}: {value:?}")]
+pub struct ParseIdError {
+    /// Kind of identifier (e.g. "force ID").
+    pub kind: &'static str,
+    /// The rejected value.
+    pub value: String,
+}
+
+/// Defines a validated string identifier with `Display`, `FromStr`,
+/// `TryFrom<&str>`, `TryFrom<String>` and serde support.
+///
+/// Only IDs built by the caller are validated. Deserialization accepts any
+/// string, so one unusual ID from the API cannot fail a whole response.
+macro_rules! string_id {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident;
+        kind = $kind:literal;
+        valid = $valid:expr;
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = concat!("Validates and wraps a ", $kind, ".")]
+            pub fn new(id: impl Into<String>) -> Result<Self, ParseIdError> {
+                let id = id.into();
+                let valid: fn(&str) -> bool = $valid;
+                if valid(&id) {
+                    Ok(Self(id))
+                } else {
+                    Err(ParseIdError {
+                        kind: $kind,
+                        value: id,
+                    })
+                }
+            }
+
+            /// Returns the identifier as a string.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = ParseIdError;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = ParseIdError;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self)
+            }
+        }
+    };
+}
+
+string_id! {
+    /// Identifier of a police force (e.g. "leicestershire").
+    ///
+    /// Must be non-empty and contain only lowercase ASCII letters, digits and
+    /// hyphens.
+    pub struct ForceId;
+    kind = "force ID";
+    valid = |id| {
+        !id.is_empty()
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    };
+}
+
+string_id! {
+    /// Force-specific identifier of a neighbourhood (e.g. "NC04").
+    ///
+    /// Neighbourhood IDs are only unique within a force; see
+    /// [`NeighbourhoodKey`]. Must be non-empty and must not contain
+    /// whitespace or the URL delimiters `/`, `?`, `#` and `&`.
+    pub struct NeighbourhoodId;
+    kind = "neighbourhood ID";
+    valid = |id| {
+        !id.is_empty()
+            && !id
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '/' | '?' | '#' | '&'))
+    };
+}
+
+string_id! {
+    /// Persistent identifier of a crime: 64 hexadecimal characters. Stable
+    /// across monthly data releases, unlike [`Crime::id`](super::Crime::id).
+    pub struct PersistentId;
+    kind = "persistent ID";
+    valid = |id| id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit());
+}
+
+impl ForceId {
+    /// Wraps an ID known to be valid, such as one from the embedded
+    /// catalogue.
+    pub(crate) fn new_unchecked(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+/// Identifier of a street-level location, as found in a street's `id` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LocationId(pub u64);
+
+impl fmt::Display for LocationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<u64> for LocationId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// A neighbourhood identified by its force and force-specific ID.
+///
+/// Displays and parses as `force/neighbourhood` (e.g.
+/// `"leicestershire/NC04"`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NeighbourhoodKey {
+    /// The force the neighbourhood belongs to.
+    pub force: ForceId,
+    /// The neighbourhood's ID within the force.
+    pub neighbourhood: NeighbourhoodId,
+}
+
+impl NeighbourhoodKey {
+    /// Creates a key from its parts.
+    pub fn new(force: ForceId, neighbourhood: NeighbourhoodId) -> Self {
+        Self {
+            force,
+            neighbourhood,
+        }
+    }
+}
+
+impl fmt::Display for NeighbourhoodKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.force, self.neighbourhood)
+    }
+}
+
+impl FromStr for NeighbourhoodKey {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseIdError {
+            kind: "neighbourhood key",
+            value: s.to_string(),
+        };
+        let (force, neighbourhood) = s.split_once('/').ok_or_else(invalid)?;
+        Ok(Self {
+            force: force.parse().map_err(|_| invalid())?,
+            neighbourhood: neighbourhood.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Serde helpers for a persistent ID that the API sends as an empty string
+/// when there is none (e.g. for anti-social behaviour).
+pub(crate) mod optional_persistent_id {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::PersistentId;
+
+    pub(crate) fn serialize<S: Serializer>(
+        id: &Option<PersistentId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(id.as_ref().map_or("", PersistentId::as_str))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PersistentId>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(id) if !id.is_empty() => Ok(Some(PersistentId(id))),
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+/// Defines an enum over a known vocabulary of API strings, with an
+/// `Unknown(String)` variant that keeps any other value verbatim.
+///
+/// Each variant maps to the string it serializes to, optionally followed by
+/// `|`-separated aliases that are also accepted when deserializing. Values
+/// and aliases match ignoring ASCII case and surrounding whitespace.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $value:literal $(| $alias:literal)*,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// Any value not recognised by this client, kept as returned by the API.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// Returns the string the API uses for this value.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Unknown(value) => value,
+                }
+            }
+
+            /// Returns `true` if the value was not recognised by this client.
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, Self::Unknown(_))
+            }
+
+            /// Matches a known value or alias. Every conversion from a string
+            /// goes through this, so they all agree.
+            fn parse_known(value: &str) -> Option<Self> {
+                match value {
+                    $($value $(| $alias)* => return Some(Self::$variant),)*
+                    _ => {}
+                }
+                let value = value.trim();
+                $(
+                    if value.eq_ignore_ascii_case($value)
+                        $(|| value.eq_ignore_ascii_case($alias))*
+                    {
+                        return Some(Self::$variant);
+                    }
+                )*
+                None
+            }
+        }
+
+        impl crate::models::unrecognised::StringEnum for $name {
+            fn unknown_value(&self) -> Option<&str> {
+                match self {
+                    Self::Unknown(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self::parse_known(value).unwrap_or_else(|| Self::Unknown(value.to_string()))
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self::parse_known(&value).unwrap_or(Self::Unknown(value))
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
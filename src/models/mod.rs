@@ -1,15 +1,36 @@
+#[macro_use]
+mod macros;
+
 mod crime;
+#[cfg(feature = "extra-fields")]
+mod extra;
 mod force;
+mod ids;
 mod neighbourhood;
 mod stop_and_search;
+mod unrecognised;
 
 pub use crime::{
-    Area, Coordinate, Crime, CrimeCategory, CrimeLastUpdated, CrimeOutcome, CrimeOutcomes,
-    Location, Outcome, OutcomeCategory, OutcomeDetail, OutcomeStatus, Street,
+    Area, BoundingBox, Coordinate, Crime, CrimeAvailability, CrimeCategory, CrimeLastUpdated,
+    CrimeOutcome, CrimeOutcomes, Location, Outcome, OutcomeCategory, OutcomeDetail, OutcomeGroup,
+    OutcomeStatus, ParseOutcomeCategoryError, Street,
 };
+#[cfg(feature = "extra-fields")]
+pub use extra::ExtraFields;
+#[cfg(feature = "extra-fields")]
+pub(crate) use extra::join_path;
 pub use force::{ContactDetails, EngagementMethod, Force, ForceDetail, SeniorOfficer};
+pub use ids::{ForceId, LocationId, NeighbourhoodId, NeighbourhoodKey, ParseIdError, PersistentId};
 pub use neighbourhood::{
     LatLng, Link, LocateNeighbourhoodResult, Neighbourhood, NeighbourhoodDetail,
     NeighbourhoodEvent, NeighbourhoodLocation, NeighbourhoodPriority,
 };
-pub use stop_and_search::{OutcomeObject, StopAndSearch, StopAndSearchType};
+pub use stop_and_search::{
+    AgeRange, Ethnicity, Gender, Legislation, ObjectOfSearch, OutcomeObject, SelfDefinedEthnicity,
+    StopAndSearch, StopAndSearchType, StopOutcome,
+};
+pub use unrecognised::{Unrecognised, UnrecognisedValue};
+
+/// Fields not recognised by this client, keyed by field name. Models only
+/// fill these in with the `extra-fields` feature.
+pub type Extra = serde_json::Map<String, serde_json::Value>;
@@ -1,22 +1,28 @@
 use serde::{Deserialize, Serialize};
 
 use super::force::ContactDetails;
+use super::{ForceId, NeighbourhoodId, NeighbourhoodKey};
 
 /// A neighbourhood summary.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Neighbourhood {
     /// Force-specific neighbourhood identifier.
     /// Note: this identifier is not unique across forces.
-    pub id: String,
+    pub id: NeighbourhoodId,
     /// Neighbourhood name.
     pub name: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Detailed information about a specific neighbourhood.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NeighbourhoodDetail {
     /// Force-specific neighbourhood identifier.
-    pub id: String,
+    pub id: NeighbourhoodId,
     /// Neighbourhood name.
     pub name: String,
     /// Description of the neighbourhood.
@@ -33,6 +39,11 @@ pub struct NeighbourhoodDetail {
     pub links: Vec<Link>,
     /// Locations associated with the neighbourhood (e.g. police stations).
     pub locations: Vec<NeighbourhoodLocation>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A latitude/longitude pair as strings (as returned by the API).
@@ -42,6 +53,11 @@ pub struct LatLng {
     pub latitude: String,
     /// Longitude.
     pub longitude: String,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A link associated with a neighbourhood.
@@ -53,6 +69,11 @@ pub struct Link {
     pub title: Option<String>,
     /// Link description.
     pub description: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A location associated with a neighbourhood (e.g. a police station).
@@ -75,6 +96,11 @@ pub struct NeighbourhoodLocation {
     pub kind: Option<String>,
     /// Location description.
     pub description: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A neighbourhood event (e.g. community meeting, surgery).
@@ -95,6 +121,11 @@ pub struct NeighbourhoodEvent {
     pub end_date: Option<String>,
     /// Contact details for the event.
     pub contact_details: Option<ContactDetails>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// A neighbourhood policing priority.
@@ -110,13 +141,30 @@ pub struct NeighbourhoodPriority {
     /// Date action was last taken (ISO format).
     #[serde(rename = "action-date")]
     pub action_date: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Result of locating a neighbourhood by coordinates.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LocateNeighbourhoodResult {
     /// Force identifier.
-    pub force: String,
+    pub force: ForceId,
     /// Neighbourhood identifier.
-    pub neighbourhood: String,
+    pub neighbourhood: NeighbourhoodId,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl LocateNeighbourhoodResult {
+    /// Returns the composite key of the located neighbourhood.
+    pub fn key(&self) -> NeighbourhoodKey {
+        NeighbourhoodKey::new(self.force.clone(), self.neighbourhood.clone())
+    }
 }
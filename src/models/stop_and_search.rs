@@ -2,15 +2,190 @@ use serde::{Deserialize, Serialize};
 
 use super::crime::Location;
 
-/// Type of stop and search.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum StopAndSearchType {
-    #[serde(rename = "Person search")]
-    Person,
-    #[serde(rename = "Vehicle search")]
-    Vehicle,
-    #[serde(rename = "Person and Vehicle search")]
-    PersonAndVehicle,
+string_enum! {
+    /// Type of stop and search.
+    pub enum StopAndSearchType {
+        Person => "Person search",
+        Vehicle => "Vehicle search",
+        PersonAndVehicle => "Person and Vehicle search",
+    }
+}
+
+string_enum! {
+    /// Gender of the person stopped.
+    pub enum Gender {
+        Male => "Male",
+        Female => "Female",
+        Other => "Other",
+    }
+}
+
+string_enum! {
+    /// Age range of the person stopped.
+    pub enum AgeRange {
+        Under10 => "under 10",
+        From10To17 => "10-17",
+        From18To24 => "18-24",
+        From25To34 => "25-34",
+        Over34 => "over 34",
+    }
+}
+
+string_enum! {
+    /// Broad ethnic group. Used for officer-defined ethnicity and as the group
+    /// of a [`SelfDefinedEthnicity`].
+    pub enum Ethnicity {
+        Asian => "Asian",
+        Black => "Black",
+        Mixed => "Mixed",
+        Other => "Other",
+        White => "White",
+    }
+}
+
+string_enum! {
+    /// Ethnicity as defined by the person stopped, using the census
+    /// categories (e.g. "White - Irish").
+    ///
+    /// Use [`SelfDefinedEthnicity::group`] and [`SelfDefinedEthnicity::subgroup`]
+    /// to split a value into its broad group and detailed subgroup.
+    pub enum SelfDefinedEthnicity {
+        AsianBangladeshi => "Asian/Asian British - Bangladeshi",
+        AsianChinese => "Asian/Asian British - Chinese",
+        AsianIndian => "Asian/Asian British - Indian",
+        AsianPakistani => "Asian/Asian British - Pakistani",
+        AsianOther => "Asian/Asian British - Any other Asian background",
+        BlackAfrican => "Black/African/Caribbean/Black British - African",
+        BlackCaribbean => "Black/African/Caribbean/Black British - Caribbean",
+        BlackOther => "Black/African/Caribbean/Black British - Any other Black/African/Caribbean background",
+        MixedWhiteAndAsian => "Mixed/Multiple ethnic groups - White and Asian",
+        MixedWhiteAndBlackAfrican => "Mixed/Multiple ethnic groups - White and Black African",
+        MixedWhiteAndBlackCaribbean => "Mixed/Multiple ethnic groups - White and Black Caribbean",
+        MixedOther => "Mixed/Multiple ethnic groups - Any other Mixed/Multiple ethnic background",
+        OtherArab => "Other ethnic group - Arab",
+        OtherAny => "Other ethnic group - Any other ethnic group",
+        NotStated => "Other ethnic group - Not stated",
+        WhiteBritish => "White - English/Welsh/Scottish/Northern Irish/British",
+        WhiteIrish => "White - Irish",
+        WhiteGypsyOrIrishTraveller => "White - Gypsy or Irish Traveller",
+        WhiteRoma => "White - Roma",
+        WhiteOther => "White - Any other White background",
+    }
+}
+
+impl SelfDefinedEthnicity {
+    /// Returns the broad ethnic group, parsed from the part of the value
+    /// before `" - "`. Works for unrecognised values that follow the same
+    /// format. `None` if the group cannot be determined.
+    pub fn group(&self) -> Option<Ethnicity> {
+        let (group, _) = self.as_str().split_once(" - ")?;
+        let ethnicity = match group.trim() {
+            "Asian/Asian British" | "Asian or Asian British" | "Asian" => Ethnicity::Asian,
+            "Black/African/Caribbean/Black British" | "Black or Black British" | "Black" => {
+                Ethnicity::Black
+            }
+            "Mixed/Multiple ethnic groups" | "Mixed" => Ethnicity::Mixed,
+            "Other ethnic group" | "Other" => Ethnicity::Other,
+            "White" => Ethnicity::White,
+            _ => return None,
+        };
+        Some(ethnicity)
+    }
+
+    /// Returns the detailed subgroup (the part after `" - "`), e.g. `"Irish"`.
+    pub fn subgroup(&self) -> Option<&str> {
+        self.as_str()
+            .split_once(" - ")
+            .map(|(_, subgroup)| subgroup.trim())
+    }
+}
+
+string_enum! {
+    /// Legislation under which a stop and search was conducted.
+    pub enum Legislation {
+        MisuseOfDrugsAct1971Section23 => "Misuse of Drugs Act 1971 (section 23)",
+        PaceSection1 => "Police and Criminal Evidence Act 1984 (section 1)",
+        PaceSection6 => "Police and Criminal Evidence Act 1984 (section 6)",
+        CriminalJusticeAndPublicOrderAct1994Section60 => "Criminal Justice and Public Order Act 1994 (section 60)",
+        CriminalJusticeAct1988Section139B => "Criminal Justice Act 1988 (section 139B)",
+        FirearmsAct1968Section47 => "Firearms Act 1968 (section 47)",
+        TerrorismAct2000Section43 => "Terrorism Act 2000 (section 43)",
+        TerrorismAct2000Section43A => "Terrorism Act 2000 (section 43A)",
+        TerrorismAct2000Section47A => "Terrorism Act 2000 (section 47A)",
+        PsychoactiveSubstancesAct2016Section36 => "Psychoactive Substances Act 2016 (s36(2))",
+        PsychoactiveSubstancesAct2016Section37 => "Psychoactive Substances Act 2016 (s37(2))",
+        CustomsAndExciseManagementAct1979Section163 => "Customs and Excise Management Act 1979 (section 163)",
+        AviationSecurityAct1982Section27 => "Aviation Security Act 1982 (section 27(1))",
+        CrossbowsAct1987Section4 => "Crossbows Act 1987 (section 4)",
+        SportingEventsAct1985Section7 => "Sporting events (Control of Alcohol etc.) Act 1985 (section 7)",
+        PublicStoresAct1875Section6 => "Public Stores Act 1875 (section 6)",
+        PoachingPreventionAct1862Section2 => "Poaching Prevention Act 1862 (section 2)",
+        DeerAct1991Section12 => "Deer Act 1991 (section 12)",
+        ConservationOfSealsAct1970Section4 => "Conservation of Seals Act 1970 (section 4)",
+        ProtectionOfBadgersAct1992Section11 => "Protection of Badgers Act 1992 (section 11)",
+        WildlifeAndCountrysideAct1981Section19 => "Wildlife and Countryside Act 1981 (section 19)",
+        HuntingAct2004Section8 => "Hunting Act 2004 (section 8)",
+        EnvironmentalProtectionAct1990Section34B => "Environmental Protection Act 1990 (section 34B )" | "Environmental Protection Act 1990 (section 34B)",
+    }
+}
+
+string_enum! {
+    /// What the officer was searching for.
+    pub enum ObjectOfSearch {
+        ControlledDrugs => "Controlled drugs",
+        OffensiveWeapons => "Offensive weapons",
+        StolenGoods => "Stolen goods",
+        ArticleForUseInTheft => "Article for use in theft",
+        ArticlesForUseInCriminalDamage => "Articles for use in criminal damage",
+        Firearms => "Firearms",
+        AnythingToThreatenOrHarm => "Anything to threaten or harm anyone",
+        EvidenceOfOffencesUnderTheAct => "Evidence of offences under the Act",
+        Fireworks => "Fireworks",
+        PsychoactiveSubstances => "Psychoactive substances",
+        EvidenceOfWildlifeOffences => "Evidence of wildlife offences",
+        GameOrPoachingEquipment => "Game or poaching equipment",
+        SealsOrHuntingEquipment => "Seals or hunting equipment",
+        Crossbows => "Crossbows",
+        GoodsOnWhichDutyNotPaid => "Goods on which duty has not been paid etc.",
+        Unavailable => "Detailed object of search unavailable",
+    }
+}
+
+string_enum! {
+    /// Outcome of a stop and search.
+    ///
+    /// Accepts the outcome names used by the street-level endpoints, the older
+    /// wordings still present in historic data, and the `outcome_object` IDs
+    /// returned by the stops-by-force endpoint. Use
+    /// [`StopAndSearch::stop_outcome`] to derive it from a record.
+    pub enum StopOutcome {
+        Arrest => "Arrest" | "Suspect arrested" | "bu-arrest",
+        CommunityResolution => "Community resolution" | "Local resolution" | "bu-community-resolution",
+        KhatOrCannabisWarning => "Khat or Cannabis warning"
+            | "Offender given drugs possession warning"
+            | "bu-khat-or-cannabis-warning"
+            | "bu-cannabis-warning",
+        PenaltyNotice => "Penalty Notice for Disorder"
+            | "Offender given penalty notice"
+            | "bu-penalty-notice",
+        Summons => "Summons / charged by post" | "Suspect summonsed to court" | "bu-summons",
+        Caution => "Caution (simple or conditional)" | "Offender cautioned" | "bu-caution",
+        NoFurtherAction => "A no further action disposal"
+            | "Nothing found - no further action"
+            | "bu-no-further-action",
+    }
+}
+
+impl StopOutcome {
+    /// Returns `true` for an arrest.
+    pub fn is_arrest(&self) -> bool {
+        matches!(self, Self::Arrest)
+    }
+
+    /// Returns `true` for any recognised outcome other than no further action.
+    pub fn is_positive(&self) -> bool {
+        !matches!(self, Self::NoFurtherAction | Self::Unknown(_))
+    }
 }
 
 /// A stop and search record.
@@ -30,17 +205,17 @@ pub struct StopAndSearch {
     /// Approximate location of the stop.
     pub location: Option<Location>,
     /// Gender of the person stopped.
-    pub gender: Option<String>,
+    pub gender: Option<Gender>,
     /// Age range of the person stopped.
-    pub age_range: Option<String>,
+    pub age_range: Option<AgeRange>,
     /// Self-defined ethnicity of the person stopped.
-    pub self_defined_ethnicity: Option<String>,
+    pub self_defined_ethnicity: Option<SelfDefinedEthnicity>,
     /// Officer-defined ethnicity of the person stopped.
-    pub officer_defined_ethnicity: Option<String>,
+    pub officer_defined_ethnicity: Option<Ethnicity>,
     /// Legislation under which the stop was conducted.
-    pub legislation: Option<String>,
+    pub legislation: Option<Legislation>,
     /// Object of the search (e.g. "Controlled drugs").
-    pub object_of_search: Option<String>,
+    pub object_of_search: Option<ObjectOfSearch>,
     /// Outcome of the stop. `None` if nothing was found.
     /// The API may return `false` instead of `null` when nothing was found.
     #[serde(default, deserialize_with = "deserialize_outcome")]
@@ -52,6 +227,72 @@ pub struct StopAndSearch {
     /// Outcome object with id and name (returned by some endpoints).
     #[serde(default)]
     pub outcome_object: Option<OutcomeObject>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl StopAndSearch {
+    /// Returns the outcome of the stop, derived from whichever outcome fields
+    /// are present.
+    ///
+    /// `outcome_object` is preferred (its ID first, then its name), then the
+    /// `outcome` string. A stop with no outcome recorded, including one where
+    /// the API returned `false`, is treated as
+    /// [`StopOutcome::NoFurtherAction`]. If every value present is
+    /// unrecognised, the first is returned as [`StopOutcome::Unknown`].
+    pub fn stop_outcome(&self) -> StopOutcome {
+        let object = self.outcome_object.as_ref();
+        let candidates = [
+            object.and_then(|o| o.id.as_deref()),
+            object.and_then(|o| o.name.as_deref()),
+            self.outcome.as_deref(),
+        ];
+        let mut present = candidates.into_iter().flatten().filter(|v| !v.is_empty());
+        let Some(first) = present.next() else {
+            return StopOutcome::NoFurtherAction;
+        };
+        std::iter::once(first)
+            .chain(present)
+            .map(StopOutcome::from)
+            .find(|outcome| !outcome.is_unknown())
+            .unwrap_or_else(|| StopOutcome::Unknown(first.to_string()))
+    }
+
+    /// Returns `true` if the stop had a positive outcome that was linked to
+    /// the object of the search, i.e. the officer found what they were
+    /// looking for.
+    pub fn is_outcome_linked_to_object(&self) -> bool {
+        self.outcome_linked_to_object_of_search == Some(true) && self.stop_outcome().is_positive()
+    }
+
+    /// Identifies the stop by the fields that describe the event itself: its
+    /// time, place and subject. Stops have no ID in the API, so this is what
+    /// matches the same stop across fetches. The outcome is left out because
+    /// forces revise it.
+    pub(crate) fn identity(&self) -> String {
+        serde_json::json!([
+            self.datetime,
+            self.kind.as_ref().map(|kind| kind.as_str()),
+            self.location.as_ref().map(|location| location.street.id.0),
+            self.location.as_ref().map(|location| &location.latitude),
+            self.location.as_ref().map(|location| &location.longitude),
+            self.involved_person,
+            self.gender.as_ref().map(|value| value.as_str()),
+            self.age_range.as_ref().map(|value| value.as_str()),
+            self.self_defined_ethnicity
+                .as_ref()
+                .map(|value| value.as_str()),
+            self.officer_defined_ethnicity
+                .as_ref()
+                .map(|value| value.as_str()),
+            self.legislation.as_ref().map(|value| value.as_str()),
+            self.object_of_search.as_ref().map(|value| value.as_str()),
+        ])
+        .to_string()
+    }
 }
 
 /// Outcome identifier returned by the stops-by-force endpoint.
@@ -61,6 +302,11 @@ pub struct OutcomeObject {
     pub id: Option<String>,
     /// Outcome name.
     pub name: Option<String>,
+    /// Fields not recognised by this client, kept as returned by the API.
+    /// Always empty without the `extra-fields` feature.
+    #[cfg_attr(feature = "extra-fields", serde(flatten))]
+    #[cfg_attr(not(feature = "extra-fields"), serde(skip))]
+    pub extra: super::Extra,
 }
 
 /// Deserializes the `outcome` field which can be a string, `false`, or `null`.
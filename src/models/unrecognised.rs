@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use super::{
+    Crime, CrimeAvailability, CrimeCategory, CrimeLastUpdated, CrimeOutcomes, Force, ForceDetail,
+    LatLng, LocateNeighbourhoodResult, Neighbourhood, NeighbourhoodDetail, NeighbourhoodEvent,
+    NeighbourhoodPriority, Outcome, SeniorOfficer, StopAndSearch,
+};
+
+/// A value the client did not recognise, with the number of times it was
+/// seen.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnrecognisedValue {
+    /// Path of the field within the record (e.g. "outcome_status.category").
+    pub field: &'static str,
+    /// The value as returned by the API.
+    pub value: String,
+    /// Number of occurrences.
+    pub count: usize,
+}
+
+/// Reports enum values that were kept as `Unknown` when a response was
+/// decoded, so that changes to the API's vocabulary can be noticed.
+///
+/// Implemented for every model returned by the [`Client`](crate::Client) and
+/// for `Vec`s of them. With the `tracing` feature the client also logs a
+/// warning for each unrecognised value it decodes.
+pub trait Unrecognised {
+    /// Calls `visit` with the field path and raw value of every
+    /// unrecognised value in `self`.
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str));
+
+    /// Returns every unrecognised value in `self`, counted and sorted by
+    /// field and value.
+    fn unrecognised_values(&self) -> Vec<UnrecognisedValue> {
+        let mut counts: BTreeMap<(&'static str, String), usize> = BTreeMap::new();
+        self.visit_unrecognised(&mut |field, value| {
+            *counts.entry((field, value.to_string())).or_default() += 1;
+        });
+        counts
+            .into_iter()
+            .map(|((field, value), count)| UnrecognisedValue {
+                field,
+                value,
+                count,
+            })
+            .collect()
+    }
+}
+
+/// Implemented by enums generated with `string_enum!`.
+pub(crate) trait StringEnum {
+    fn unknown_value(&self) -> Option<&str>;
+}
+
+fn check<E: StringEnum>(
+    visit: &mut dyn FnMut(&'static str, &str),
+    field: &'static str,
+    value: Option<&E>,
+) {
+    if let Some(unknown) = value.and_then(StringEnum::unknown_value) {
+        visit(field, unknown);
+    }
+}
+
+impl<T: Unrecognised> Unrecognised for Vec<T> {
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str)) {
+        for item in self {
+            item.visit_unrecognised(visit);
+        }
+    }
+}
+
+impl Unrecognised for Crime {
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str)) {
+        let category = self.outcome_status.as_ref().map(|status| &status.category);
+        check(visit, "outcome_status.category", category);
+    }
+}
+
+impl Unrecognised for Outcome {
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str)) {
+        check(visit, "category.code", Some(&self.category.code));
+        let category = self.crime.outcome_status.as_ref().map(|s| &s.category);
+        check(visit, "crime.outcome_status.category", category);
+    }
+}
+
+impl Unrecognised for CrimeOutcomes {
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str)) {
+        let category = self.crime.outcome_status.as_ref().map(|s| &s.category);
+        check(visit, "crime.outcome_status.category", category);
+        for outcome in &self.outcomes {
+            check(
+                visit,
+                "outcomes.category.code",
+                Some(&outcome.category.code),
+            );
+        }
+    }
+}
+
+impl Unrecognised for StopAndSearch {
+    fn visit_unrecognised(&self, visit: &mut dyn FnMut(&'static str, &str)) {
+        check(visit, "type", self.kind.as_ref());
+        check(visit, "gender", self.gender.as_ref());
+        check(visit, "age_range", self.age_range.as_ref());
+        check(
+            visit,
+            "self_defined_ethnicity",
+            self.self_defined_ethnicity.as_ref(),
+        );
+        check(
+            visit,
+            "officer_defined_ethnicity",
+            self.officer_defined_ethnicity.as_ref(),
+        );
+        check(visit, "legislation", self.legislation.as_ref());
+        check(visit, "object_of_search", self.object_of_search.as_ref());
+        check(visit, "outcome", Some(&self.stop_outcome()));
+    }
+}
+
+macro_rules! fully_typed {
+    ($($ty:ty),*) => {
+        $(impl Unrecognised for $ty {
+            fn visit_unrecognised(&self, _visit: &mut dyn FnMut(&'static str, &str)) {}
+        })*
+    };
+}
+
+fully_typed!(
+    CrimeAvailability,
+    CrimeCategory,
+    CrimeLastUpdated,
+    Force,
+    ForceDetail,
+    LatLng,
+    LocateNeighbourhoodResult,
+    Neighbourhood,
+    NeighbourhoodDetail,
+    NeighbourhoodEvent,
+    NeighbourhoodPriority,
+    SeniorOfficer
+);
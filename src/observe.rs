@@ -0,0 +1,46 @@
+//! Forwards instrumentation events to whichever of the `tracing` and
+//! `metrics` features are enabled. Every function is a no-op without them,
+//! and some arguments are only used by one of the two.
+#![cfg_attr(
+    not(all(feature = "tracing", feature = "metrics")),
+    allow(unused_variables)
+)]
+
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "tracing")]
+use crate::trace;
+
+/// A request joined (or did not join) another caller's in-flight request.
+pub(crate) fn coalesced(endpoint: &'static str, coalesced: bool) {
+    #[cfg(feature = "tracing")]
+    trace::record_coalesced(coalesced);
+    #[cfg(feature = "metrics")]
+    metrics::record_coalesced(endpoint, coalesced);
+}
+
+/// A request is about to be retried. `attempt` counts retries from 1.
+pub(crate) fn retry(endpoint: &'static str, attempt: u32) {
+    #[cfg(feature = "tracing")]
+    trace::record_retry(attempt);
+    #[cfg(feature = "metrics")]
+    metrics::record_retry(endpoint);
+}
+
+/// A request was looked up in the response cache.
+pub(crate) fn cache(endpoint: &'static str, hit: bool) {
+    #[cfg(feature = "tracing")]
+    trace::record_cache(hit);
+    #[cfg(feature = "metrics")]
+    metrics::record_cache(endpoint, hit);
+}
+
+/// A request waited for the rate limiter before being sent.
+pub(crate) fn rate_limit_wait(endpoint: &'static str, wait: Duration) {
+    #[cfg(feature = "tracing")]
+    trace::record_rate_limit_wait(wait);
+    #[cfg(feature = "metrics")]
+    metrics::record_rate_limit_wait(endpoint, wait);
+}
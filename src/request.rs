@@ -0,0 +1,185 @@
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+#[cfg(feature = "extra-fields")]
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "extra-fields")]
+use crate::models::ExtraFields;
+use crate::models::{
+    Area, Coordinate, CrimeLastUpdated, CrimeOutcomes, ForceDetail, LocateNeighbourhoodResult,
+    LocationId, NeighbourhoodDetail, Unrecognised,
+};
+
+/// A single API call made by the [`Client`](crate::Client).
+///
+/// Besides the URL, a request carries the endpoint name and the key
+/// parameters it was made with, so that middleware and instrumentation can
+/// act on calls without parsing URLs.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Name of the client method (e.g. "street_level_crimes").
+    pub endpoint: &'static str,
+    /// Fully built request URL. Middleware may rewrite it.
+    pub url: String,
+    /// Extra headers to send with the request.
+    pub headers: HeaderMap,
+    /// Force identifier, if the endpoint takes one.
+    pub force: Option<String>,
+    /// Neighbourhood identifier, if the endpoint takes one.
+    pub neighbourhood: Option<String>,
+    /// Crime category slug, if the endpoint takes one.
+    pub category: Option<String>,
+    /// Month filter (format: `YYYY-MM`), if one was given.
+    pub month: Option<String>,
+    /// Area queried, if any. Point lookups are recorded as
+    /// [`RequestArea::Point`].
+    pub area: Option<RequestArea>,
+    /// Crime persistent ID, if the endpoint takes one.
+    pub persistent_id: Option<String>,
+}
+
+impl Request {
+    pub(crate) fn new(endpoint: &'static str, url: String) -> Self {
+        Self {
+            endpoint,
+            url,
+            headers: HeaderMap::new(),
+            force: None,
+            neighbourhood: None,
+            category: None,
+            month: None,
+            area: None,
+            persistent_id: None,
+        }
+    }
+
+    pub(crate) fn force(mut self, force: &str) -> Self {
+        self.force = Some(force.to_string());
+        self
+    }
+
+    pub(crate) fn neighbourhood(mut self, neighbourhood: &str) -> Self {
+        self.neighbourhood = Some(neighbourhood.to_string());
+        self
+    }
+
+    pub(crate) fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    pub(crate) fn month(mut self, month: Option<&str>) -> Self {
+        self.month = month.map(str::to_string);
+        self
+    }
+
+    pub(crate) fn area(mut self, area: &Area) -> Self {
+        self.area = Some(area.into());
+        self
+    }
+
+    pub(crate) fn persistent_id(mut self, persistent_id: &str) -> Self {
+        self.persistent_id = Some(persistent_id.to_string());
+        self
+    }
+}
+
+/// The area a [`Request`] was made for.
+///
+/// Polygons are summarised by their number of vertices, which avoids
+/// copying every vertex into each request; the full polygon is in the URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestArea {
+    /// A point, or the centre of a 1 mile radius search.
+    Point(Coordinate),
+    /// A custom polygon with this many vertices.
+    Polygon { vertices: usize },
+    /// A location ID.
+    LocationId(LocationId),
+}
+
+impl From<&Area> for RequestArea {
+    fn from(area: &Area) -> Self {
+        match area {
+            Area::Point(coordinate) => Self::Point(coordinate.clone()),
+            Area::Custom(coordinates) => Self::Polygon {
+                vertices: coordinates.len(),
+            },
+            Area::LocationId(id) => Self::LocationId(*id),
+        }
+    }
+}
+
+/// A successful HTTP response, before it is decoded into a model.
+///
+/// Unsuccessful responses are surfaced as [`Error::Api`](crate::Error::Api)
+/// instead.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Raw response body (JSON).
+    pub body: Bytes,
+}
+
+impl Response {
+    /// Creates a response with no headers, e.g. to short-circuit a request
+    /// from middleware.
+    pub fn new(status: u16, body: impl Into<Bytes>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Bounds required of every type the client decodes responses into.
+#[cfg(not(feature = "extra-fields"))]
+pub(crate) trait Decode: DeserializeOwned + ResultCount + Unrecognised {}
+
+#[cfg(not(feature = "extra-fields"))]
+impl<T: DeserializeOwned + ResultCount + Unrecognised> Decode for T {}
+
+/// Bounds required of every type the client decodes responses into.
+#[cfg(feature = "extra-fields")]
+pub(crate) trait Decode:
+    DeserializeOwned + ResultCount + Unrecognised + Serialize + ExtraFields
+{
+}
+
+#[cfg(feature = "extra-fields")]
+impl<T: DeserializeOwned + ResultCount + Unrecognised + Serialize + ExtraFields> Decode for T {}
+
+/// Number of records in a decoded response, used by instrumentation.
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub(crate) trait ResultCount {
+    fn result_count(&self) -> usize;
+}
+
+impl<T> ResultCount for Vec<T> {
+    fn result_count(&self) -> usize {
+        self.len()
+    }
+}
+
+macro_rules! single_result {
+    ($($ty:ty),*) => {
+        $(impl ResultCount for $ty {
+            fn result_count(&self) -> usize {
+                1
+            }
+        })*
+    };
+}
+
+single_result!(
+    CrimeLastUpdated,
+    CrimeOutcomes,
+    ForceDetail,
+    LocateNeighbourhoodResult,
+    NeighbourhoodDetail
+);
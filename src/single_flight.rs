@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use futures_util::FutureExt;
+use futures_util::future::{BoxFuture, Shared};
+
+use crate::error::Error;
+use crate::request::Response;
+
+type SharedFetch = Shared<BoxFuture<'static, Result<Response, Error>>>;
+
+/// A request in flight, with the number of callers awaiting it.
+struct InFlight {
+    /// Distinguishes this request from later ones for the same key.
+    id: u64,
+    fetch: SharedFetch,
+    waiters: usize,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    in_flight: HashMap<String, InFlight>,
+}
+
+impl State {
+    /// Removes the entry for `key` if it still belongs to request `id`.
+    fn remove(&mut self, key: &str, id: u64) {
+        if self.in_flight.get(key).is_some_and(|entry| entry.id == id) {
+            self.in_flight.remove(key);
+        }
+    }
+}
+
+/// De-duplicates concurrent requests for the same key.
+///
+/// The first caller for a key starts the request; every caller that arrives
+/// while it is still in flight awaits the same future and receives a clone of
+/// its result. The entry is removed as soon as the request completes, or
+/// when every caller awaiting it has been cancelled, so later callers always
+/// start a fresh request.
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    state: Arc<Mutex<State>>,
+}
+
+impl SingleFlight {
+    /// Runs `fetch` unless a request for `key` is already in flight, in which
+    /// case its result is awaited instead. The returned flag is `true` when
+    /// the result came from another caller's request.
+    pub(crate) async fn run<F>(&self, key: String, fetch: F) -> (Result<Response, Error>, bool)
+    where
+        F: Future<Output = Result<Response, Error>> + Send + 'static,
+    {
+        let (waiter, shared, coalesced) = {
+            let mut state = self.state.lock().unwrap();
+            let (id, shared, coalesced) = match state.in_flight.get_mut(&key) {
+                Some(entry) => {
+                    entry.waiters += 1;
+                    (entry.id, entry.fetch.clone(), true)
+                }
+                None => {
+                    let id = state.next_id;
+                    state.next_id += 1;
+                    let shared = {
+                        let (state, key) = (Arc::clone(&self.state), key.clone());
+                        async move {
+                            let result = fetch.await;
+                            state.lock().unwrap().remove(&key, id);
+                            result
+                        }
+                        .boxed()
+                        .shared()
+                    };
+                    state.in_flight.insert(
+                        key.clone(),
+                        InFlight {
+                            id,
+                            fetch: shared.clone(),
+                            waiters: 1,
+                        },
+                    );
+                    (id, shared, false)
+                }
+            };
+            let waiter = Waiter {
+                state: &self.state,
+                key,
+                id,
+            };
+            (waiter, shared, coalesced)
+        };
+        let result = shared.await;
+        drop(waiter);
+        (result, coalesced)
+    }
+}
+
+/// Releases a caller's interest in a request when it finishes or is
+/// cancelled. The last caller to leave an unfinished request removes it, so
+/// an abandoned request is never resumed by a later caller.
+struct Waiter<'a> {
+    state: &'a Mutex<State>,
+    key: String,
+    id: u64,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        let Some(entry) = state.in_flight.get_mut(&self.key) else {
+            return;
+        };
+        if entry.id == self.id {
+            entry.waiters -= 1;
+            if entry.waiters == 0 {
+                state.remove(&self.key, self.id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancelled_request_is_not_resumed() {
+        let single_flight = SingleFlight::default();
+        let pending = single_flight.run("key".to_string(), std::future::pending());
+        // Poll once so the request is registered, then cancel it.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), pending)
+                .await
+                .is_err()
+        );
+        assert!(single_flight.state.lock().unwrap().in_flight.is_empty());
+
+        let (result, coalesced) = single_flight
+            .run("key".to_string(), async { Ok(Response::new(200, "[]")) })
+            .await;
+        assert!(!coalesced);
+        assert_eq!(result.unwrap().body, "[]");
+    }
+
+    #[tokio::test]
+    async fn test_request_survives_while_a_waiter_remains() {
+        let single_flight = SingleFlight::default();
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        let first = single_flight.run("key".to_string(), async move {
+            receiver.await.unwrap();
+            Ok(Response::new(200, "first"))
+        });
+        let second = single_flight.run("key".to_string(), async { unreachable!() });
+        let cancelled = async {
+            let _ = tokio::time::timeout(Duration::from_millis(10), first).await;
+            sender.send(()).unwrap();
+        };
+        let ((), (result, coalesced)) = tokio::join!(cancelled, second);
+        assert!(coalesced);
+        assert_eq!(result.unwrap().body, "first");
+        assert!(single_flight.state.lock().unwrap().in_flight.is_empty());
+    }
+}
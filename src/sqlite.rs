@@ -0,0 +1,1096 @@
+//! SQLite persistence for fetched records, available with the `sqlite`
+//! feature.
+//!
+//! A [`Store`] keeps crimes, outcomes, stop and searches, forces,
+//! neighbourhoods and boundaries in a normalized schema. Every `upsert_*`
+//! method is idempotent, so the same month can be imported repeatedly, and
+//! the query methods return the crate's models.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use uk_police_api::sqlite::{CrimeQuery, Store};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = uk_police_api::Client::new();
+//! let force = "leicestershire".parse()?;
+//! let crimes = client
+//!     .crimes_no_location("all-crime", &force, Some("2024-01"))
+//!     .await?;
+//!
+//! let mut store = Store::open("police.db")?;
+//! store.upsert_crimes(&crimes, Some(&force))?;
+//!
+//! let burglaries = store.crimes(&CrimeQuery {
+//!     force: Some(force),
+//!     category: Some("burglary".to_string()),
+//!     ..CrimeQuery::default()
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OptionalExtension, Row, Transaction, params, params_from_iter};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::models::{
+    BoundingBox, Crime, CrimeOutcome, CrimeOutcomes, Force, ForceId, LatLng, Location,
+    Neighbourhood, NeighbourhoodDetail, NeighbourhoodKey, Outcome, PersistentId, StopAndSearch,
+};
+
+/// Version of the schema created by [`Store`], stored in `PRAGMA user_version`.
+pub const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS forces (
+    id   TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS streets (
+    id        INTEGER PRIMARY KEY,
+    name      TEXT NOT NULL,
+    latitude  TEXT NOT NULL,
+    longitude TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS crimes (
+    id               INTEGER PRIMARY KEY,
+    persistent_id    TEXT,
+    category         TEXT NOT NULL,
+    month            TEXT NOT NULL,
+    force_id         TEXT,
+    street_id        INTEGER REFERENCES streets (id),
+    location_type    TEXT,
+    location_subtype TEXT NOT NULL,
+    context          TEXT NOT NULL,
+    outcome_category TEXT,
+    outcome_date     TEXT
+);
+CREATE UNIQUE INDEX IF NOT EXISTS crimes_persistent_id ON crimes (persistent_id)
+    WHERE persistent_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS crimes_month ON crimes (month, category);
+CREATE INDEX IF NOT EXISTS crimes_force ON crimes (force_id, month);
+
+CREATE TABLE IF NOT EXISTS outcomes (
+    persistent_id TEXT NOT NULL,
+    category      TEXT NOT NULL,
+    category_name TEXT NOT NULL,
+    date          TEXT NOT NULL,
+    person_id     TEXT,
+    PRIMARY KEY (persistent_id, category, date)
+);
+
+CREATE TABLE IF NOT EXISTS stops (
+    key                                 TEXT PRIMARY KEY,
+    month                               TEXT,
+    force_id                            TEXT,
+    type                                TEXT,
+    involved_person                     INTEGER,
+    datetime                            TEXT,
+    operation                           INTEGER,
+    operation_name                      TEXT,
+    street_id                           INTEGER REFERENCES streets (id),
+    gender                              TEXT,
+    age_range                           TEXT,
+    self_defined_ethnicity              TEXT,
+    officer_defined_ethnicity           TEXT,
+    legislation                         TEXT,
+    object_of_search                    TEXT,
+    outcome                             TEXT,
+    outcome_linked_to_object_of_search  INTEGER,
+    removal_of_more_than_outer_clothing INTEGER,
+    outcome_object_id                   TEXT,
+    outcome_object_name                 TEXT
+);
+CREATE INDEX IF NOT EXISTS stops_month ON stops (month);
+CREATE INDEX IF NOT EXISTS stops_force ON stops (force_id, month);
+
+CREATE TABLE IF NOT EXISTS neighbourhoods (
+    force_id        TEXT NOT NULL,
+    id              TEXT NOT NULL,
+    name            TEXT NOT NULL,
+    description     TEXT,
+    population      TEXT,
+    url_force       TEXT,
+    centre_latitude  TEXT,
+    centre_longitude TEXT,
+    contact_details TEXT,
+    links           TEXT,
+    locations       TEXT,
+    PRIMARY KEY (force_id, id)
+);
+
+CREATE TABLE IF NOT EXISTS boundaries (
+    force_id         TEXT NOT NULL,
+    neighbourhood_id TEXT NOT NULL,
+    seq              INTEGER NOT NULL,
+    latitude         TEXT NOT NULL,
+    longitude        TEXT NOT NULL,
+    PRIMARY KEY (force_id, neighbourhood_id, seq)
+);
+";
+
+/// Error returned by a [`Store`].
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("failed to decode stored record: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("unsupported schema version {0} (expected {SCHEMA_VERSION})")]
+    SchemaVersion(i32),
+}
+
+/// Filters for [`Store::crimes`]. Unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrimeQuery {
+    /// Month the crime was recorded (format: `YYYY-MM`).
+    pub month: Option<String>,
+    /// Force the crimes were stored under.
+    pub force: Option<ForceId>,
+    /// Crime category slug (e.g. "burglary").
+    pub category: Option<String>,
+    /// Area the crime's location must fall within. Crimes without a
+    /// location never match.
+    pub within: Option<BoundingBox>,
+}
+
+/// Filters for [`Store::stops`]. Unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StopQuery {
+    /// Month of the stop (format: `YYYY-MM`), taken from its `datetime`.
+    pub month: Option<String>,
+    /// Force the stops were stored under.
+    pub force: Option<ForceId>,
+    /// Area the stop's location must fall within. Stops without a location
+    /// never match.
+    pub within: Option<BoundingBox>,
+}
+
+/// A SQLite database of fetched records.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens (or creates) a database file and creates any missing tables.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a temporary in-memory database.
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Uses an existing connection, creating any missing tables.
+    pub fn from_connection(conn: Connection) -> Result<Self, StoreError> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        match version {
+            0 => {
+                conn.execute_batch(SCHEMA)?;
+                conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            }
+            SCHEMA_VERSION => {}
+            other => return Err(StoreError::SchemaVersion(other)),
+        }
+        Ok(Self { conn })
+    }
+
+    /// Returns the underlying connection, e.g. for ad hoc SQL.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Inserts or updates forces by ID.
+    pub fn upsert_forces(&mut self, forces: &[Force]) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO forces (id, name) VALUES (?1, ?2)
+                 ON CONFLICT (id) DO UPDATE SET name = excluded.name",
+            )?;
+            for force in forces {
+                insert.execute(params![force.id.as_str(), force.name])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates crimes, along with their streets.
+    ///
+    /// Crimes are matched by `persistent_id` when they have one, so a crime
+    /// the API reissues under a new `id` replaces the stored row, and by
+    /// `id` otherwise.
+    ///
+    /// `force` records which force the crimes belong to, for
+    /// [`CrimeQuery::force`]; pass `None` to keep any force already stored.
+    pub fn upsert_crimes(
+        &mut self,
+        crimes: &[Crime],
+        force: Option<&ForceId>,
+    ) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        for crime in crimes {
+            insert_crime(&tx, crime, force)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates street-level outcomes and the crimes they refer
+    /// to. Outcomes are keyed by crime `persistent_id`, category and date;
+    /// outcomes for crimes without a persistent ID are skipped.
+    pub fn upsert_outcomes(&mut self, outcomes: &[Outcome]) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        for outcome in outcomes {
+            insert_crime(&tx, &outcome.crime, None)?;
+            if let Some(persistent_id) = &outcome.crime.persistent_id {
+                insert_outcome(&tx, persistent_id, outcome)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates a crime and all of its outcomes, as returned by
+    /// [`Client::outcomes_for_crime`](crate::Client::outcomes_for_crime).
+    pub fn upsert_crime_outcomes(&mut self, record: &CrimeOutcomes) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        insert_crime(&tx, &record.crime, None)?;
+        if let Some(persistent_id) = &record.crime.persistent_id {
+            for outcome in &record.outcomes {
+                insert_outcome(&tx, persistent_id, outcome)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates stop and searches.
+    ///
+    /// Stops have no ID in the API, so each is keyed by its time, location
+    /// and subject fields. Re-importing the same data is idempotent, but two
+    /// stops identical in every one of those fields are stored once. `force`
+    /// works as for [`Store::upsert_crimes`].
+    pub fn upsert_stops(
+        &mut self,
+        stops: &[StopAndSearch],
+        force: Option<&ForceId>,
+    ) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO stops (
+                    key, month, force_id, type, involved_person, datetime, operation,
+                    operation_name, street_id, gender, age_range, self_defined_ethnicity,
+                    officer_defined_ethnicity, legislation, object_of_search, outcome,
+                    outcome_linked_to_object_of_search, removal_of_more_than_outer_clothing,
+                    outcome_object_id, outcome_object_name
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                           ?16, ?17, ?18, ?19, ?20)
+                 ON CONFLICT (key) DO UPDATE SET
+                    force_id = COALESCE(excluded.force_id, stops.force_id),
+                    operation = excluded.operation,
+                    operation_name = excluded.operation_name,
+                    outcome = excluded.outcome,
+                    outcome_linked_to_object_of_search =
+                        excluded.outcome_linked_to_object_of_search,
+                    removal_of_more_than_outer_clothing =
+                        excluded.removal_of_more_than_outer_clothing,
+                    outcome_object_id = excluded.outcome_object_id,
+                    outcome_object_name = excluded.outcome_object_name",
+            )?;
+            for stop in stops {
+                if let Some(location) = &stop.location {
+                    insert_street(&tx, location)?;
+                }
+                let month = stop
+                    .datetime
+                    .as_deref()
+                    .and_then(|datetime| datetime.get(..7));
+                insert.execute(params![
+                    stop.identity(),
+                    month,
+                    force.map(ForceId::as_str),
+                    stop.kind.as_ref().map(|kind| kind.as_str()),
+                    stop.involved_person,
+                    stop.datetime,
+                    stop.operation,
+                    stop.operation_name,
+                    stop.location.as_ref().map(|location| location.street.id.0),
+                    stop.gender.as_ref().map(|value| value.as_str()),
+                    stop.age_range.as_ref().map(|value| value.as_str()),
+                    stop.self_defined_ethnicity
+                        .as_ref()
+                        .map(|value| value.as_str()),
+                    stop.officer_defined_ethnicity
+                        .as_ref()
+                        .map(|value| value.as_str()),
+                    stop.legislation.as_ref().map(|value| value.as_str()),
+                    stop.object_of_search.as_ref().map(|value| value.as_str()),
+                    stop.outcome,
+                    stop.outcome_linked_to_object_of_search,
+                    stop.removal_of_more_than_outer_clothing,
+                    stop.outcome_object
+                        .as_ref()
+                        .and_then(|object| object.id.clone()),
+                    stop.outcome_object
+                        .as_ref()
+                        .and_then(|object| object.name.clone()),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates a force's neighbourhoods by ID, keeping any
+    /// details already stored with [`Store::upsert_neighbourhood_detail`].
+    pub fn upsert_neighbourhoods(
+        &mut self,
+        force: &ForceId,
+        neighbourhoods: &[Neighbourhood],
+    ) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO neighbourhoods (force_id, id, name) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (force_id, id) DO UPDATE SET name = excluded.name",
+            )?;
+            for neighbourhood in neighbourhoods {
+                insert.execute(params![
+                    force.as_str(),
+                    neighbourhood.id.as_str(),
+                    neighbourhood.name
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or updates a neighbourhood's details.
+    pub fn upsert_neighbourhood_detail(
+        &mut self,
+        force: &ForceId,
+        detail: &NeighbourhoodDetail,
+    ) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT INTO neighbourhoods (
+                force_id, id, name, description, population, url_force, centre_latitude,
+                centre_longitude, contact_details, links, locations
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT (force_id, id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                population = excluded.population,
+                url_force = excluded.url_force,
+                centre_latitude = excluded.centre_latitude,
+                centre_longitude = excluded.centre_longitude,
+                contact_details = excluded.contact_details,
+                links = excluded.links,
+                locations = excluded.locations",
+            params![
+                force.as_str(),
+                detail.id.as_str(),
+                detail.name,
+                detail.description,
+                detail.population,
+                detail.url_force,
+                detail.centre.latitude,
+                detail.centre.longitude,
+                serde_json::to_string(&detail.contact_details)?,
+                serde_json::to_string(&detail.links)?,
+                serde_json::to_string(&detail.locations)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the stored boundary of a neighbourhood.
+    pub fn upsert_boundary(
+        &mut self,
+        key: &NeighbourhoodKey,
+        boundary: &[LatLng],
+    ) -> Result<(), StoreError> {
+        let (force, neighbourhood) = (key.force.as_str(), key.neighbourhood.as_str());
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM boundaries WHERE force_id = ?1 AND neighbourhood_id = ?2",
+            params![force, neighbourhood],
+        )?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO boundaries (force_id, neighbourhood_id, seq, latitude, longitude)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (seq, point) in boundary.iter().enumerate() {
+                insert.execute(params![
+                    force,
+                    neighbourhood,
+                    seq,
+                    point.latitude,
+                    point.longitude
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns stored forces, sorted by ID.
+    pub fn forces(&self) -> Result<Vec<Force>, StoreError> {
+        self.query_models("SELECT id, name FROM forces ORDER BY id", [], |row| {
+            Ok(json!({ "id": row.get::<_, String>(0)?, "name": row.get::<_, String>(1)? }))
+        })
+    }
+
+    /// Returns stored crimes matching `query`, sorted by month and ID.
+    pub fn crimes(&self, query: &CrimeQuery) -> Result<Vec<Crime>, StoreError> {
+        let mut filter = Filter::default();
+        filter.eq("c.month", query.month.clone());
+        filter.eq("c.force_id", query.force.as_ref().map(ForceId::to_string));
+        filter.eq("c.category", query.category.clone());
+        filter.within("s", query.within);
+        let sql = format!(
+            "SELECT {CRIME_COLUMNS} FROM crimes c LEFT JOIN streets s ON s.id = c.street_id
+             {} ORDER BY c.month, c.id",
+            filter.where_clause()
+        );
+        self.query_models(&sql, params_from_iter(filter.params), crime_json)
+    }
+
+    /// Returns the stored crime with the given persistent ID, if any.
+    pub fn crime(&self, persistent_id: &PersistentId) -> Result<Option<Crime>, StoreError> {
+        let sql = format!(
+            "SELECT {CRIME_COLUMNS} FROM crimes c LEFT JOIN streets s ON s.id = c.street_id
+             WHERE c.persistent_id = ?1 ORDER BY c.month DESC LIMIT 1"
+        );
+        let value = self
+            .conn
+            .query_row(&sql, [persistent_id.as_str()], crime_json)
+            .optional()?;
+        Ok(value.map(serde_json::from_value).transpose()?)
+    }
+
+    /// Returns stored outcomes of a crime, oldest first.
+    pub fn outcomes(&self, persistent_id: &PersistentId) -> Result<Vec<CrimeOutcome>, StoreError> {
+        self.query_models(
+            "SELECT category, category_name, date, person_id FROM outcomes
+             WHERE persistent_id = ?1 ORDER BY date, category",
+            [persistent_id.as_str()],
+            |row| {
+                Ok(json!({
+                    "category": { "code": row.get::<_, String>(0)?, "name": row.get::<_, String>(1)? },
+                    "date": row.get::<_, String>(2)?,
+                    "person_id": row.get::<_, Option<String>>(3)?,
+                }))
+            },
+        )
+    }
+
+    /// Returns stored stop and searches matching `query`, sorted by time and
+    /// then in the order they were first stored.
+    pub fn stops(&self, query: &StopQuery) -> Result<Vec<StopAndSearch>, StoreError> {
+        let mut filter = Filter::default();
+        filter.eq("t.month", query.month.clone());
+        filter.eq("t.force_id", query.force.as_ref().map(ForceId::to_string));
+        filter.within("s", query.within);
+        let sql = format!(
+            "SELECT t.type, t.involved_person, t.datetime, t.operation, t.operation_name,
+                    s.id, s.name, s.latitude, s.longitude, t.gender, t.age_range,
+                    t.self_defined_ethnicity, t.officer_defined_ethnicity, t.legislation,
+                    t.object_of_search, t.outcome, t.outcome_linked_to_object_of_search,
+                    t.removal_of_more_than_outer_clothing, t.outcome_object_id,
+                    t.outcome_object_name
+             FROM stops t LEFT JOIN streets s ON s.id = t.street_id
+             {} ORDER BY t.datetime, t.rowid",
+            filter.where_clause()
+        );
+        self.query_models(&sql, params_from_iter(filter.params), |row| {
+            let outcome_object = match (
+                row.get::<_, Option<String>>(18)?,
+                row.get::<_, Option<String>>(19)?,
+            ) {
+                (None, None) => Value::Null,
+                (id, name) => json!({ "id": id, "name": name }),
+            };
+            Ok(json!({
+                "type": row.get::<_, Option<String>>(0)?,
+                "involved_person": row.get::<_, Option<bool>>(1)?,
+                "datetime": row.get::<_, Option<String>>(2)?,
+                "operation": row.get::<_, Option<bool>>(3)?,
+                "operation_name": row.get::<_, Option<String>>(4)?,
+                "location": location_json(row, 5)?,
+                "gender": row.get::<_, Option<String>>(9)?,
+                "age_range": row.get::<_, Option<String>>(10)?,
+                "self_defined_ethnicity": row.get::<_, Option<String>>(11)?,
+                "officer_defined_ethnicity": row.get::<_, Option<String>>(12)?,
+                "legislation": row.get::<_, Option<String>>(13)?,
+                "object_of_search": row.get::<_, Option<String>>(14)?,
+                "outcome": row.get::<_, Option<String>>(15)?,
+                "outcome_linked_to_object_of_search": row.get::<_, Option<bool>>(16)?,
+                "removal_of_more_than_outer_clothing": row.get::<_, Option<bool>>(17)?,
+                "outcome_object": outcome_object,
+            }))
+        })
+    }
+
+    /// Returns the stored neighbourhoods of a force, sorted by ID.
+    pub fn neighbourhoods(&self, force: &ForceId) -> Result<Vec<Neighbourhood>, StoreError> {
+        self.query_models(
+            "SELECT id, name FROM neighbourhoods WHERE force_id = ?1 ORDER BY id",
+            [force.as_str()],
+            |row| Ok(json!({ "id": row.get::<_, String>(0)?, "name": row.get::<_, String>(1)? })),
+        )
+    }
+
+    /// Returns the stored details of a neighbourhood, if
+    /// [`Store::upsert_neighbourhood_detail`] was called for it.
+    pub fn neighbourhood_detail(
+        &self,
+        key: &NeighbourhoodKey,
+    ) -> Result<Option<NeighbourhoodDetail>, StoreError> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT id, name, description, population, url_force, centre_latitude,
+                        centre_longitude, contact_details, links, locations
+                 FROM neighbourhoods
+                 WHERE force_id = ?1 AND id = ?2 AND contact_details IS NOT NULL",
+                [key.force.as_str(), key.neighbourhood.as_str()],
+                |row| {
+                    let nested = |index| -> rusqlite::Result<Value> {
+                        let text: String = row.get(index)?;
+                        serde_json::from_str(&text).map_err(|err| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                index,
+                                rusqlite::types::Type::Text,
+                                Box::new(err),
+                            )
+                        })
+                    };
+                    Ok(json!({
+                        "id": row.get::<_, String>(0)?,
+                        "name": row.get::<_, String>(1)?,
+                        "description": row.get::<_, Option<String>>(2)?,
+                        "population": row.get::<_, Option<String>>(3)?,
+                        "url_force": row.get::<_, Option<String>>(4)?,
+                        "centre": {
+                            "latitude": row.get::<_, String>(5)?,
+                            "longitude": row.get::<_, String>(6)?,
+                        },
+                        "contact_details": nested(7)?,
+                        "links": nested(8)?,
+                        "locations": nested(9)?,
+                    }))
+                },
+            )
+            .optional()?;
+        Ok(value.map(serde_json::from_value).transpose()?)
+    }
+
+    /// Returns the stored boundary of a neighbourhood, in order.
+    pub fn boundary(&self, key: &NeighbourhoodKey) -> Result<Vec<LatLng>, StoreError> {
+        self.query_models(
+            "SELECT latitude, longitude FROM boundaries
+             WHERE force_id = ?1 AND neighbourhood_id = ?2 ORDER BY seq",
+            [key.force.as_str(), key.neighbourhood.as_str()],
+            |row| {
+                Ok(json!({
+                    "latitude": row.get::<_, String>(0)?,
+                    "longitude": row.get::<_, String>(1)?,
+                }))
+            },
+        )
+    }
+
+    /// Runs a query, maps each row to JSON and decodes it into a model.
+    fn query_models<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+        to_json: impl FnMut(&Row<'_>) -> rusqlite::Result<Value>,
+    ) -> Result<Vec<T>, StoreError> {
+        let mut statement = self.conn.prepare(sql)?;
+        let rows = statement.query_map(params, to_json)?;
+        let mut models = Vec::new();
+        for row in rows {
+            models.push(serde_json::from_value(row?)?);
+        }
+        Ok(models)
+    }
+}
+
+const CRIME_COLUMNS: &str = "c.id, c.persistent_id, c.category, c.month, c.location_type,
+    c.location_subtype, c.context, c.outcome_category, c.outcome_date,
+    s.id, s.name, s.latitude, s.longitude";
+
+/// Builds the JSON form of a [`Crime`] from a row of [`CRIME_COLUMNS`].
+fn crime_json(row: &Row<'_>) -> rusqlite::Result<Value> {
+    let outcome_status = match row.get::<_, Option<String>>(7)? {
+        Some(category) => json!({ "category": category, "date": row.get::<_, String>(8)? }),
+        None => Value::Null,
+    };
+    Ok(json!({
+        "id": row.get::<_, u64>(0)?,
+        "persistent_id": row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+        "category": row.get::<_, String>(2)?,
+        "month": row.get::<_, String>(3)?,
+        "location_type": row.get::<_, Option<String>>(4)?,
+        "location_subtype": row.get::<_, String>(5)?,
+        "context": row.get::<_, String>(6)?,
+        "outcome_status": outcome_status,
+        "location": location_json(row, 9)?,
+    }))
+}
+
+/// Builds the JSON form of a [`Location`] from street `id`, `name`,
+/// `latitude` and `longitude` columns starting at `first`.
+fn location_json(row: &Row<'_>, first: usize) -> rusqlite::Result<Value> {
+    let Some(id) = row.get::<_, Option<u64>>(first)? else {
+        return Ok(Value::Null);
+    };
+    Ok(json!({
+        "latitude": row.get::<_, String>(first + 2)?,
+        "longitude": row.get::<_, String>(first + 3)?,
+        "street": { "id": id, "name": row.get::<_, String>(first + 1)? },
+    }))
+}
+
+fn insert_street(tx: &Transaction<'_>, location: &Location) -> rusqlite::Result<()> {
+    tx.prepare_cached(
+        "INSERT INTO streets (id, name, latitude, longitude) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (id) DO UPDATE SET
+            name = excluded.name,
+            latitude = excluded.latitude,
+            longitude = excluded.longitude",
+    )?
+    .execute(params![
+        location.street.id.0,
+        location.street.name,
+        location.latitude,
+        location.longitude
+    ])?;
+    Ok(())
+}
+
+fn insert_crime(
+    tx: &Transaction<'_>,
+    crime: &Crime,
+    force: Option<&ForceId>,
+) -> rusqlite::Result<()> {
+    if let Some(location) = &crime.location {
+        insert_street(tx, location)?;
+    }
+    // Outcomes embed a crime without its latest outcome status, so a missing
+    // status never overwrites a stored one.
+    tx.prepare_cached(
+        "INSERT INTO crimes (
+            id, persistent_id, category, month, force_id, street_id, location_type,
+            location_subtype, context, outcome_category, outcome_date
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT (persistent_id) WHERE persistent_id IS NOT NULL DO UPDATE SET
+            id = excluded.id,
+            category = excluded.category,
+            month = excluded.month,
+            force_id = COALESCE(excluded.force_id, crimes.force_id),
+            street_id = excluded.street_id,
+            location_type = excluded.location_type,
+            location_subtype = excluded.location_subtype,
+            context = excluded.context,
+            outcome_category = COALESCE(excluded.outcome_category, crimes.outcome_category),
+            outcome_date = COALESCE(excluded.outcome_date, crimes.outcome_date)
+         ON CONFLICT (id) DO UPDATE SET
+            persistent_id = excluded.persistent_id,
+            category = excluded.category,
+            month = excluded.month,
+            force_id = COALESCE(excluded.force_id, crimes.force_id),
+            street_id = excluded.street_id,
+            location_type = excluded.location_type,
+            location_subtype = excluded.location_subtype,
+            context = excluded.context,
+            outcome_category = COALESCE(excluded.outcome_category, crimes.outcome_category),
+            outcome_date = COALESCE(excluded.outcome_date, crimes.outcome_date)",
+    )?
+    .execute(params![
+        crime.id,
+        crime.persistent_id.as_ref().map(PersistentId::as_str),
+        crime.category,
+        crime.month,
+        force.map(ForceId::as_str),
+        crime.location.as_ref().map(|location| location.street.id.0),
+        crime.location_type,
+        crime.location_subtype,
+        crime.context,
+        crime
+            .outcome_status
+            .as_ref()
+            .map(|status| status.category.as_str()),
+        crime.outcome_status.as_ref().map(|status| &status.date),
+    ])?;
+    Ok(())
+}
+
+/// Fields shared by [`Outcome`] and [`CrimeOutcome`].
+struct OutcomeRow<'a> {
+    category: &'a str,
+    category_name: &'a str,
+    date: &'a str,
+    person_id: Option<&'a str>,
+}
+
+impl<'a> From<&'a Outcome> for OutcomeRow<'a> {
+    fn from(outcome: &'a Outcome) -> Self {
+        Self {
+            category: outcome.category.code.as_str(),
+            category_name: &outcome.category.name,
+            date: &outcome.date,
+            person_id: outcome.person_id.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a CrimeOutcome> for OutcomeRow<'a> {
+    fn from(outcome: &'a CrimeOutcome) -> Self {
+        Self {
+            category: outcome.category.code.as_str(),
+            category_name: &outcome.category.name,
+            date: &outcome.date,
+            person_id: outcome.person_id.as_deref(),
+        }
+    }
+}
+
+fn insert_outcome<'a>(
+    tx: &Transaction<'_>,
+    persistent_id: &PersistentId,
+    outcome: impl Into<OutcomeRow<'a>>,
+) -> rusqlite::Result<()> {
+    let outcome = outcome.into();
+    tx.prepare_cached(
+        "INSERT INTO outcomes (persistent_id, category, category_name, date, person_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (persistent_id, category, date) DO UPDATE SET
+            category_name = excluded.category_name,
+            person_id = excluded.person_id",
+    )?
+    .execute(params![
+        persistent_id.as_str(),
+        outcome.category,
+        outcome.category_name,
+        outcome.date,
+        outcome.person_id
+    ])?;
+    Ok(())
+}
+
+/// A `WHERE` clause built from optional conditions.
+#[derive(Default)]
+struct Filter {
+    conditions: Vec<String>,
+    params: Vec<SqlValue>,
+}
+
+impl Filter {
+    fn eq(&mut self, column: &str, value: Option<String>) {
+        if let Some(value) = value {
+            self.params.push(SqlValue::Text(value));
+            self.conditions
+                .push(format!("{column} = ?{}", self.params.len()));
+        }
+    }
+
+    fn within(&mut self, streets: &str, bounds: Option<BoundingBox>) {
+        let Some(bounds) = bounds else { return };
+        for (column, op, value) in [
+            ("latitude", ">=", bounds.south),
+            ("latitude", "<=", bounds.north),
+            ("longitude", ">=", bounds.west),
+            ("longitude", "<=", bounds.east),
+        ] {
+            self.params.push(SqlValue::Real(value));
+            self.conditions.push(format!(
+                "CAST({streets}.{column} AS REAL) {op} ?{}",
+                self.params.len()
+            ));
+        }
+    }
+
+    fn where_clause(&self) -> String {
+        if self.conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.conditions.join(" AND "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERSISTENT_ID: &str = "dd6e56f90d1bdd7bc7482af17852369f263203d9a688fac42ec53bf48485d8f1";
+
+    fn crime(id: u64, category: &str, month: &str, lat: &str, street: u64) -> Crime {
+        serde_json::from_value(json!({
+            "category": category,
+            "persistent_id": if id == 1 { PERSISTENT_ID } else { "" },
+            "location_subtype": "",
+            "id": id,
+            "location": {
+                "latitude": lat,
+                "street": { "id": street, "name": "On or near High Street" },
+                "longitude": "-1.13"
+            },
+            "context": "",
+            "month": month,
+            "location_type": "Force",
+            "outcome_status": { "category": "Under investigation", "date": month }
+        }))
+        .unwrap()
+    }
+
+    fn stop(datetime: &str, gender: &str) -> StopAndSearch {
+        serde_json::from_value(json!({
+            "type": "Person search",
+            "involved_person": true,
+            "datetime": datetime,
+            "operation": null,
+            "operation_name": null,
+            "location": {
+                "latitude": "52.63",
+                "street": { "id": 10, "name": "On or near Park Road" },
+                "longitude": "-1.13"
+            },
+            "gender": gender,
+            "age_range": "18-24",
+            "self_defined_ethnicity": null,
+            "officer_defined_ethnicity": "White",
+            "legislation": "Misuse of Drugs Act 1971 (section 23)",
+            "object_of_search": "Controlled drugs",
+            "outcome": "A no further action disposal",
+            "outcome_linked_to_object_of_search": null,
+            "removal_of_more_than_outer_clothing": false
+        }))
+        .unwrap()
+    }
+
+    fn count(store: &Store, table: &str) -> i64 {
+        store
+            .connection()
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_crimes_round_trip_and_queries() {
+        let mut store = Store::open_in_memory().unwrap();
+        let force: ForceId = "leicestershire".parse().unwrap();
+        let crimes = vec![
+            crime(1, "burglary", "2024-01", "52.63", 100),
+            crime(2, "drugs", "2024-01", "52.70", 101),
+            crime(3, "burglary", "2024-02", "52.63", 100),
+        ];
+        store.upsert_crimes(&crimes, Some(&force)).unwrap();
+        store.upsert_crimes(&crimes, None).unwrap();
+        assert_eq!(count(&store, "crimes"), 3);
+        assert_eq!(count(&store, "streets"), 2);
+
+        assert_eq!(store.crimes(&CrimeQuery::default()).unwrap(), crimes);
+        let january_burglary = store
+            .crimes(&CrimeQuery {
+                month: Some("2024-01".to_string()),
+                force: Some(force.clone()),
+                category: Some("burglary".to_string()),
+                ..CrimeQuery::default()
+            })
+            .unwrap();
+        assert_eq!(january_burglary, [crimes[0].clone()]);
+
+        let within = BoundingBox {
+            south: 52.6,
+            west: -1.2,
+            north: 52.65,
+            east: -1.1,
+        };
+        let ids: Vec<u64> = store
+            .crimes(&CrimeQuery {
+                within: Some(within),
+                ..CrimeQuery::default()
+            })
+            .unwrap()
+            .iter()
+            .map(|crime| crime.id)
+            .collect();
+        assert_eq!(ids, [1, 3]);
+
+        let other: ForceId = "kent".parse().unwrap();
+        let none = store
+            .crimes(&CrimeQuery {
+                force: Some(other),
+                ..CrimeQuery::default()
+            })
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_reissued_crime_replaces_stored_row() {
+        let mut store = Store::open_in_memory().unwrap();
+        let force: ForceId = "leicestershire".parse().unwrap();
+        store
+            .upsert_crimes(
+                &[crime(1, "burglary", "2024-01", "52.63", 100)],
+                Some(&force),
+            )
+            .unwrap();
+
+        // The API reissues the crime under a new id with the same persistent ID.
+        let mut reissued = crime(1, "burglary", "2024-01", "52.63", 100);
+        reissued.id = 7;
+        store.upsert_crimes(&[reissued.clone()], None).unwrap();
+        assert_eq!(count(&store, "crimes"), 1);
+
+        let persistent_id = reissued.persistent_id.clone().unwrap();
+        assert_eq!(store.crime(&persistent_id).unwrap(), Some(reissued));
+        let stored = store
+            .crimes(&CrimeQuery {
+                force: Some(force),
+                ..CrimeQuery::default()
+            })
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].id, 7);
+    }
+
+    #[test]
+    fn test_outcomes_are_keyed_by_persistent_id() {
+        let mut store = Store::open_in_memory().unwrap();
+        let record: CrimeOutcomes = serde_json::from_value(json!({
+            "crime": crime(1, "burglary", "2024-01", "52.63", 100),
+            "outcomes": [
+                {
+                    "category": { "code": "under-investigation", "name": "Under investigation" },
+                    "date": "2024-01",
+                    "person_id": null
+                },
+                {
+                    "category": { "code": "charged", "name": "Suspect charged" },
+                    "date": "2024-03",
+                    "person_id": null
+                }
+            ]
+        }))
+        .unwrap();
+        store.upsert_crime_outcomes(&record).unwrap();
+        store.upsert_crime_outcomes(&record).unwrap();
+
+        let persistent_id: PersistentId = PERSISTENT_ID.parse().unwrap();
+        assert_eq!(store.outcomes(&persistent_id).unwrap(), record.outcomes);
+        assert_eq!(store.crime(&persistent_id).unwrap(), Some(record.crime));
+    }
+
+    #[test]
+    fn test_stops_are_idempotent() {
+        let mut store = Store::open_in_memory().unwrap();
+        let force: ForceId = "leicestershire".parse().unwrap();
+        let stops = vec![
+            stop("2024-01-15T12:30:00+00:00", "Male"),
+            stop("2024-01-15T12:30:00+00:00", "Female"),
+            stop("2024-02-01T09:00:00+00:00", "Male"),
+        ];
+        store.upsert_stops(&stops, None).unwrap();
+        store.upsert_stops(&stops, Some(&force)).unwrap();
+        assert_eq!(count(&store, "stops"), 3);
+
+        assert_eq!(store.stops(&StopQuery::default()).unwrap(), stops);
+        let january = store
+            .stops(&StopQuery {
+                month: Some("2024-01".to_string()),
+                force: Some(force.clone()),
+                ..StopQuery::default()
+            })
+            .unwrap();
+        assert_eq!(january, stops[..2]);
+
+        // A revised outcome updates the stored stop rather than adding one.
+        let mut revised = stops[2].clone();
+        revised.outcome = Some("Arrest".to_string());
+        store.upsert_stops(&[revised], Some(&force)).unwrap();
+        assert_eq!(count(&store, "stops"), 3);
+        let february = store
+            .stops(&StopQuery {
+                month: Some("2024-02".to_string()),
+                ..StopQuery::default()
+            })
+            .unwrap();
+        assert_eq!(february[0].outcome.as_deref(), Some("Arrest"));
+    }
+
+    #[test]
+    fn test_neighbourhoods_and_boundaries() {
+        let mut store = Store::open_in_memory().unwrap();
+        let key: NeighbourhoodKey = "leicestershire/NC04".parse().unwrap();
+        let neighbourhoods: Vec<Neighbourhood> =
+            serde_json::from_value(json!([{ "id": "NC04", "name": "City Centre" }])).unwrap();
+        store
+            .upsert_neighbourhoods(&key.force, &neighbourhoods)
+            .unwrap();
+        assert_eq!(store.neighbourhoods(&key.force).unwrap(), neighbourhoods);
+        assert_eq!(store.neighbourhood_detail(&key).unwrap(), None);
+
+        let detail: NeighbourhoodDetail = serde_json::from_value(json!({
+            "id": "NC04",
+            "name": "City Centre",
+            "description": null,
+            "population": "0",
+            "url_force": null,
+            "contact_details": { "email": "city@example.org" },
+            "centre": { "latitude": "52.63", "longitude": "-1.13" },
+            "links": [],
+            "locations": []
+        }))
+        .unwrap();
+        store
+            .upsert_neighbourhood_detail(&key.force, &detail)
+            .unwrap();
+        store
+            .upsert_neighbourhoods(&key.force, &neighbourhoods)
+            .unwrap();
+        assert_eq!(store.neighbourhood_detail(&key).unwrap(), Some(detail));
+
+        let boundary: Vec<LatLng> = serde_json::from_value(json!([
+            { "latitude": "52.6", "longitude": "-1.1" },
+            { "latitude": "52.7", "longitude": "-1.1" },
+            { "latitude": "52.7", "longitude": "-1.2" }
+        ]))
+        .unwrap();
+        store.upsert_boundary(&key, &boundary).unwrap();
+        store.upsert_boundary(&key, &boundary[..2]).unwrap();
+        assert_eq!(store.boundary(&key).unwrap(), boundary[..2]);
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        assert!(matches!(
+            Store::from_connection(conn),
+            Err(StoreError::SchemaVersion(2))
+        ));
+    }
+}
@@ -0,0 +1,647 @@
+//! Incremental sync of monthly force data.
+//!
+//! A [`Syncer`] compares the API's `crime-last-updated` date and month
+//! availability with a persisted [`SyncState`], then fetches only the
+//! force months it has not seen before into a [`Sink`]. State is saved after
+//! every month written, so a sync that fails part way resumes where it
+//! stopped.
+//!
+//! A month is fetched again when it is republished: whenever
+//! `crime-last-updated` advances, the most recent months already synced (see
+//! [`Syncer::republish_window`]) are treated as replaced, even if a new month
+//! appeared in the same release.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use uk_police_api::Client;
+//! use uk_police_api::sync::{FileSink, Syncer};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut syncer = Syncer::new(Client::new())
+//!     .forces(["leicestershire".parse()?])
+//!     .since("2024-01")
+//!     .state_file("sync-state.json");
+//! let report = syncer.run(&mut FileSink::new("data")).await?;
+//! println!("fetched {} months, {} records", report.fetched.len(), report.records);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::models::{Crime, ForceId, StopAndSearch};
+
+/// Error type returned by a [`Sink`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const DEFAULT_REPUBLISH_WINDOW: usize = 1;
+
+/// A monthly per-force dataset that can be synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dataset {
+    /// Stop and searches reported by the force
+    /// ([`Client::stops_force`]). Only fetched for months the force is
+    /// listed as having published.
+    Stops,
+    /// Crimes the force could not map to a location
+    /// ([`Client::crimes_no_location`], all categories).
+    CrimesNoLocation,
+}
+
+impl Dataset {
+    /// Returns the dataset's name as used in state files and paths.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stops => "stops",
+            Self::CrimesNoLocation => "crimes_no_location",
+        }
+    }
+}
+
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One dataset for one force and month.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SyncKey {
+    pub dataset: Dataset,
+    pub force: ForceId,
+    /// Month of the data (format: `YYYY-MM`).
+    pub month: String,
+}
+
+impl fmt::Display for SyncKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.dataset, self.force, self.month)
+    }
+}
+
+/// Records fetched for a [`SyncKey`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncRecords {
+    Stops(Vec<StopAndSearch>),
+    CrimesNoLocation(Vec<Crime>),
+}
+
+impl SyncRecords {
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Stops(stops) => stops.len(),
+            Self::CrimesNoLocation(crimes) => crimes.len(),
+        }
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A month of data handed to a [`Sink`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncBatch {
+    pub key: SyncKey,
+    pub records: SyncRecords,
+}
+
+/// Destination for synced data.
+///
+/// A republished month is written again with the same key, so sinks should
+/// replace rather than append.
+pub trait Sink {
+    /// Writes one month of data.
+    fn write(&mut self, batch: &SyncBatch) -> Result<(), BoxError>;
+}
+
+/// Writes each month as JSON to `<dir>/<dataset>/<force>/<month>.json`.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    dir: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the path a key is written to.
+    pub fn path(&self, key: &SyncKey) -> PathBuf {
+        self.dir
+            .join(key.dataset.as_str())
+            .join(key.force.as_str())
+            .join(format!("{}.json", key.month))
+    }
+}
+
+impl Sink for FileSink {
+    fn write(&mut self, batch: &SyncBatch) -> Result<(), BoxError> {
+        let json = match &batch.records {
+            SyncRecords::Stops(stops) => serde_json::to_vec(stops)?,
+            SyncRecords::CrimesNoLocation(crimes) => serde_json::to_vec(crimes)?,
+        };
+        write_atomic(&self.path(&batch.key), &json)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Sink for crate::sqlite::Store {
+    fn write(&mut self, batch: &SyncBatch) -> Result<(), BoxError> {
+        let force = Some(&batch.key.force);
+        match &batch.records {
+            SyncRecords::Stops(stops) => self.upsert_stops(stops, force)?,
+            SyncRecords::CrimesNoLocation(crimes) => self.upsert_crimes(crimes, force)?,
+        }
+        Ok(())
+    }
+}
+
+/// A [`Sink`] that calls a function for each batch. Created by [`callback`].
+pub struct Callback<F>(F);
+
+/// Wraps a function as a [`Sink`].
+pub fn callback<F>(f: F) -> Callback<F>
+where
+    F: FnMut(&SyncBatch) -> Result<(), BoxError>,
+{
+    Callback(f)
+}
+
+impl<F> Sink for Callback<F>
+where
+    F: FnMut(&SyncBatch) -> Result<(), BoxError>,
+{
+    fn write(&mut self, batch: &SyncBatch) -> Result<(), BoxError> {
+        (self.0)(batch)
+    }
+}
+
+/// What a [`Syncer`] has seen and fetched, persisted between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// `crime-last-updated` date seen on the last run.
+    pub crime_last_updated: Option<String>,
+    /// Months available on the last run, with the forces that had published
+    /// stop and search data for each.
+    pub availability: BTreeMap<String, BTreeSet<ForceId>>,
+    /// Force months written to the sink since they were last published.
+    pub fetched: BTreeSet<SyncKey>,
+}
+
+impl SyncState {
+    /// Loads state from a JSON file, or returns an empty state if the file
+    /// does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SyncError> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(SyncError::StateDecode),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(SyncError::State(err)),
+        }
+    }
+
+    /// Saves state as JSON, replacing the file atomically.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SyncError> {
+        let json = serde_json::to_vec_pretty(self).map_err(SyncError::StateDecode)?;
+        write_atomic(path.as_ref(), &json)?;
+        Ok(())
+    }
+}
+
+/// Error returned by [`Syncer::run`].
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    #[error("failed to check for new data: {0}")]
+    Availability(Error),
+
+    #[error("failed to fetch {key}: {error}")]
+    Fetch { key: SyncKey, error: Error },
+
+    #[error("sink failed to write {key}: {error}")]
+    Sink { key: SyncKey, error: BoxError },
+
+    #[error("failed to read or write sync state: {0}")]
+    State(#[from] io::Error),
+
+    #[error("invalid sync state: {0}")]
+    StateDecode(serde_json::Error),
+}
+
+/// Outcome of a [`Syncer::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Months that were not available on the previous run.
+    pub new_months: Vec<String>,
+    /// Months treated as republished and fetched again.
+    pub republished: Vec<String>,
+    /// Force months written to the sink, in order.
+    pub fetched: Vec<SyncKey>,
+    /// Total number of records written.
+    pub records: usize,
+}
+
+/// Fetches newly published force months into a [`Sink`].
+pub struct Syncer {
+    client: Client,
+    forces: Option<Vec<ForceId>>,
+    datasets: Vec<Dataset>,
+    since: Option<String>,
+    republish_window: usize,
+    state_file: Option<PathBuf>,
+    state: SyncState,
+}
+
+impl Syncer {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            forces: None,
+            datasets: vec![Dataset::Stops, Dataset::CrimesNoLocation],
+            since: None,
+            republish_window: DEFAULT_REPUBLISH_WINDOW,
+            state_file: None,
+            state: SyncState::default(),
+        }
+    }
+
+    /// Sets the forces to sync. Defaults to every force the API lists.
+    pub fn forces(mut self, forces: impl IntoIterator<Item = ForceId>) -> Self {
+        self.forces = Some(forces.into_iter().collect());
+        self
+    }
+
+    /// Sets the datasets to sync. Defaults to all of them.
+    pub fn datasets(mut self, datasets: impl IntoIterator<Item = Dataset>) -> Self {
+        self.datasets = datasets.into_iter().collect();
+        self
+    }
+
+    /// Ignores months before `month` (format: `YYYY-MM`). By default every
+    /// available month is synced.
+    pub fn since(mut self, month: impl Into<String>) -> Self {
+        self.since = Some(month.into());
+        self
+    }
+
+    /// Sets how many of the most recent months are fetched again when the
+    /// API republishes data. Defaults to 1.
+    pub fn republish_window(mut self, months: usize) -> Self {
+        self.republish_window = months;
+        self
+    }
+
+    /// Loads state from, and saves it to, a JSON file on every run.
+    pub fn state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Starts from a state returned by [`Syncer::state`] on an earlier run.
+    /// Ignored if a [`Syncer::state_file`] is set.
+    pub fn resume(mut self, state: SyncState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &SyncState {
+        &self.state
+    }
+
+    /// Checks for new or republished data and writes every missing force
+    /// month to `sink`, oldest first.
+    ///
+    /// On error, everything written so far is recorded in the state, so the
+    /// next run continues from the failed month.
+    pub async fn run(&mut self, sink: &mut (impl Sink + ?Sized)) -> Result<SyncReport, SyncError> {
+        if let Some(path) = &self.state_file {
+            self.state = SyncState::load(path)?;
+        }
+        let mut report = SyncReport::default();
+
+        let last_updated = self
+            .client
+            .crime_last_updated()
+            .await
+            .map_err(SyncError::Availability)?
+            .date;
+        let availability: BTreeMap<String, BTreeSet<ForceId>> = self
+            .client
+            .crime_availability()
+            .await
+            .map_err(SyncError::Availability)?
+            .into_iter()
+            .filter(|month| self.since.as_ref().is_none_or(|since| month.date >= *since))
+            .map(|month| (month.date, month.stop_and_search.into_iter().collect()))
+            .collect();
+
+        report.new_months = availability
+            .keys()
+            .filter(|month| !self.state.availability.contains_key(*month))
+            .cloned()
+            .collect();
+        let updated = self
+            .state
+            .crime_last_updated
+            .as_ref()
+            .is_some_and(|previous| *previous != last_updated);
+        if updated {
+            report.republished = availability
+                .keys()
+                .rev()
+                .filter(|month| !report.new_months.contains(month))
+                .take(self.republish_window)
+                .cloned()
+                .collect();
+            report.republished.reverse();
+            self.state
+                .fetched
+                .retain(|key| !report.republished.contains(&key.month));
+        }
+        self.state.crime_last_updated = Some(last_updated);
+        self.state.availability = availability;
+        self.save()?;
+
+        let forces = match &self.forces {
+            Some(forces) => forces.clone(),
+            None => self
+                .client
+                .forces()
+                .await
+                .map_err(SyncError::Availability)?
+                .into_iter()
+                .map(|force| force.id)
+                .collect(),
+        };
+        for key in self.pending(&forces) {
+            let records = self.fetch(&key).await.map_err(|error| SyncError::Fetch {
+                key: key.clone(),
+                error,
+            })?;
+            report.records += records.len();
+            let batch = SyncBatch {
+                key: key.clone(),
+                records,
+            };
+            sink.write(&batch).map_err(|error| SyncError::Sink {
+                key: key.clone(),
+                error,
+            })?;
+            self.state.fetched.insert(key.clone());
+            self.save()?;
+            report.fetched.push(key);
+        }
+        Ok(report)
+    }
+
+    /// Force months that are available but not yet fetched, oldest first.
+    fn pending(&self, forces: &[ForceId]) -> Vec<SyncKey> {
+        let mut pending = Vec::new();
+        for (month, stops_forces) in &self.state.availability {
+            for force in forces {
+                for &dataset in &self.datasets {
+                    if dataset == Dataset::Stops && !stops_forces.contains(force) {
+                        continue;
+                    }
+                    let key = SyncKey {
+                        dataset,
+                        force: force.clone(),
+                        month: month.clone(),
+                    };
+                    if !self.state.fetched.contains(&key) {
+                        pending.push(key);
+                    }
+                }
+            }
+        }
+        pending
+    }
+
+    async fn fetch(&self, key: &SyncKey) -> Result<SyncRecords, Error> {
+        let month = Some(key.month.as_str());
+        Ok(match key.dataset {
+            Dataset::Stops => SyncRecords::Stops(self.client.stops_force(&key.force, month).await?),
+            Dataset::CrimesNoLocation => SyncRecords::CrimesNoLocation(
+                self.client
+                    .crimes_no_location("all-crime", &key.force, month)
+                    .await?,
+            ),
+        })
+    }
+
+    fn save(&self) -> Result<(), SyncError> {
+        match &self.state_file {
+            Some(path) => self.state.save(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes `bytes` to a temporary file beside `path`, then renames it into
+/// place so readers never see a partial file.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, bytes)?;
+    fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn mock_release(server: &MockServer, last_updated: &str, months: &[&str]) {
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/crime-last-updated"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "date": last_updated })))
+            .mount(server)
+            .await;
+        let availability: Vec<_> = months
+            .iter()
+            .rev()
+            .map(|month| {
+                // Kent only published stop and search data for January.
+                let forces = if *month == "2024-01" {
+                    vec!["leicestershire", "kent"]
+                } else {
+                    vec!["leicestershire"]
+                };
+                json!({ "date": month, "stop-and-search": forces })
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/crimes-street-dates"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(availability))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/stops-force"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "type": "Person search",
+                "datetime": "2024-01-15T12:30:00+00:00"
+            }])))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crimes-no-location"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(server)
+            .await;
+    }
+
+    fn syncer(server: &MockServer) -> Syncer {
+        Syncer::new(Client::new().with_base_url(server.uri()))
+            .forces(["leicestershire".parse().unwrap(), "kent".parse().unwrap()])
+            .since("2024-01")
+    }
+
+    fn keys(report: &SyncReport) -> Vec<String> {
+        report.fetched.iter().map(ToString::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn test_fetches_only_new_and_republished_months() {
+        let server = MockServer::start().await;
+        mock_release(&server, "2024-02-01", &["2023-12", "2024-01", "2024-02"]).await;
+        let mut syncer = syncer(&server);
+        let mut batches = Vec::new();
+        let mut sink = callback(|batch: &SyncBatch| {
+            batches.push(batch.key.to_string());
+            Ok(())
+        });
+
+        let report = syncer.run(&mut sink).await.unwrap();
+        assert_eq!(report.new_months, ["2024-01", "2024-02"]);
+        assert_eq!(
+            keys(&report),
+            [
+                "stops/leicestershire/2024-01",
+                "crimes_no_location/leicestershire/2024-01",
+                "stops/kent/2024-01",
+                "crimes_no_location/kent/2024-01",
+                "stops/leicestershire/2024-02",
+                "crimes_no_location/leicestershire/2024-02",
+                "crimes_no_location/kent/2024-02",
+            ]
+        );
+        assert_eq!(report.records, 3);
+
+        // Nothing new.
+        let report = syncer.run(&mut sink).await.unwrap();
+        assert_eq!(report, SyncReport::default());
+
+        // Data republished without a new month.
+        mock_release(&server, "2024-02-15", &["2024-01", "2024-02"]).await;
+        let report = syncer.run(&mut sink).await.unwrap();
+        assert_eq!(report.republished, ["2024-02"]);
+        assert_eq!(report.fetched.len(), 3);
+
+        // A new month, released alongside a republished one.
+        mock_release(&server, "2024-03-01", &["2024-01", "2024-02", "2024-03"]).await;
+        let report = syncer.run(&mut sink).await.unwrap();
+        assert_eq!(report.new_months, ["2024-03"]);
+        assert_eq!(report.republished, ["2024-02"]);
+        assert_eq!(
+            keys(&report),
+            [
+                "stops/leicestershire/2024-02",
+                "crimes_no_location/leicestershire/2024-02",
+                "crimes_no_location/kent/2024-02",
+                "stops/leicestershire/2024-03",
+                "crimes_no_location/leicestershire/2024-03",
+                "crimes_no_location/kent/2024-03",
+            ]
+        );
+        assert_eq!(batches.len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_resumes_from_state_file() {
+        let server = MockServer::start().await;
+        mock_release(&server, "2024-01-01", &["2024-01"]).await;
+        let dir = std::env::temp_dir().join(format!("uk-police-api-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let state_file = dir.join("state.json");
+
+        let mut failing = callback(|batch: &SyncBatch| {
+            if batch.key.force.as_str() == "kent" {
+                Err("disk full".into())
+            } else {
+                Ok(())
+            }
+        });
+        let err = syncer(&server)
+            .state_file(&state_file)
+            .run(&mut failing)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, SyncError::Sink { key, .. } if key.force.as_str() == "kent"));
+
+        let state = SyncState::load(&state_file).unwrap();
+        assert_eq!(state.crime_last_updated.as_deref(), Some("2024-01-01"));
+        assert_eq!(state.fetched.len(), 2);
+
+        let mut sink = FileSink::new(dir.join("data"));
+        let report = syncer(&server)
+            .state_file(&state_file)
+            .run(&mut sink)
+            .await
+            .unwrap();
+        assert_eq!(
+            keys(&report),
+            ["stops/kent/2024-01", "crimes_no_location/kent/2024-01"]
+        );
+        let stops: Vec<StopAndSearch> =
+            serde_json::from_slice(&fs::read(sink.path(&report.fetched[0])).unwrap()).unwrap();
+        assert_eq!(stops.len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_uses_force_and_month() {
+        let server = MockServer::start().await;
+        mock_release(&server, "2024-01-01", &["2024-01"]).await;
+        Mock::given(method("GET"))
+            .and(path("/stops-force"))
+            .and(query_param("force", "kent"))
+            .and(query_param("date", "2024-01"))
+            .respond_with(ResponseTemplate::new(500))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let err = syncer(&server)
+            .datasets([Dataset::Stops])
+            .run(&mut callback(|_: &SyncBatch| Ok(())))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SyncError::Fetch { key, error: Error::Api { status: 500, .. } }
+                if key.to_string() == "stops/kent/2024-01"
+        ));
+    }
+}
@@ -0,0 +1,621 @@
+//! Covering a large area with street-level crime queries.
+//!
+//! The API returns at most 10,000 crimes per request and responds with
+//! `503 Service Unavailable` above that, so a whole force cannot be fetched
+//! with one polygon. A [`Tiler`] covers one or more polygons (such as a
+//! force's neighbourhood boundaries) with a grid of rectangular tiles,
+//! splits any tile whose query hits the cap into quarters, and removes
+//! crimes returned by more than one tile.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use uk_police_api::Client;
+//! use uk_police_api::tiling::Tiler;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new();
+//! let force = "leicestershire".parse()?;
+//! let mut tiler = Tiler::new();
+//! for neighbourhood in client.neighbourhoods(&force).await? {
+//!     let key = uk_police_api::NeighbourhoodKey::new(force.clone(), neighbourhood.id);
+//!     tiler = tiler.boundary(&client.neighbourhood_boundary(&key).await?);
+//! }
+//!
+//! let harvest = tiler.harvest_crimes(&client, "all-crime", Some("2024-01")).await;
+//! println!("{} crimes from {} tiles", harvest.crimes.len(), harvest.tiles.len());
+//! for failure in &harvest.failed {
+//!     eprintln!("{:?} failed: {}", failure.tile.bounds, failure.error);
+//! }
+//!
+//! // Start next month from the refined tiles.
+//! let tiler = tiler.plan(harvest.plan());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::models::{Area, BoundingBox, Coordinate, Crime, LatLng};
+
+/// Most crimes the API returns for one street-level query.
+pub const RESULT_CAP: usize = 10_000;
+
+const DEFAULT_TILE_SIZE: f64 = 0.05;
+const DEFAULT_MAX_DEPTH: u32 = 6;
+/// Most grid tiles along either side of the initial grid.
+const MAX_GRID_SIDE: f64 = 256.0;
+/// About a millimetre, in degrees.
+const TOUCH_TOLERANCE: f64 = 1e-8;
+
+/// A rectangular query area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    pub bounds: BoundingBox,
+    /// Number of times the tile has been split from an initial grid tile.
+    pub depth: u32,
+}
+
+impl Tile {
+    /// Returns the tile as an [`Area::Custom`] for a query.
+    pub fn area(&self) -> Area {
+        self.bounds.into()
+    }
+
+    /// Splits the tile into four equal quarters.
+    pub fn split(&self) -> [Tile; 4] {
+        let BoundingBox {
+            south,
+            west,
+            north,
+            east,
+        } = self.bounds;
+        let (lat, lng) = ((south + north) / 2.0, (west + east) / 2.0);
+        let depth = self.depth + 1;
+        [
+            (south, west, lat, lng),
+            (south, lng, lat, east),
+            (lat, west, north, lng),
+            (lat, lng, north, east),
+        ]
+        .map(|(south, west, north, east)| Tile {
+            bounds: BoundingBox {
+                south,
+                west,
+                north,
+                east,
+            },
+            depth,
+        })
+    }
+}
+
+/// A tile that was queried, with the number of crimes it returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileResult {
+    pub tile: Tile,
+    pub count: usize,
+}
+
+/// A tile whose query failed, with the error it returned.
+#[derive(Debug, Clone)]
+pub struct TileFailure {
+    pub tile: Tile,
+    pub error: Error,
+}
+
+/// Crimes collected by [`Tiler::harvest_crimes`].
+#[derive(Debug, Clone)]
+pub struct Harvest {
+    /// Every crime found, each once, in the order first seen.
+    pub crimes: Vec<Crime>,
+    /// The tiles that were queried successfully, after any splitting.
+    pub tiles: Vec<TileResult>,
+    /// The tiles whose query failed, so their crimes are missing.
+    pub failed: Vec<TileFailure>,
+    /// Number of crimes dropped because another tile already returned them.
+    pub duplicates: usize,
+}
+
+impl Harvest {
+    /// Returns `true` if every tile was queried successfully.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Returns the tiles that were queried, including those that failed, to
+    /// start a later harvest of the same area with [`Tiler::plan`] and avoid
+    /// repeating the splits.
+    pub fn plan(&self) -> Vec<Tile> {
+        self.tiles
+            .iter()
+            .map(|result| result.tile)
+            .chain(self.failed.iter().map(|failure| failure.tile))
+            .collect()
+    }
+}
+
+/// Plans and runs tiled street-level crime queries over polygons.
+#[derive(Debug, Clone)]
+pub struct Tiler {
+    polygons: Vec<Vec<Coordinate>>,
+    tile_size: f64,
+    max_depth: u32,
+    plan: Option<Vec<Tile>>,
+}
+
+impl Tiler {
+    pub fn new() -> Self {
+        Self {
+            polygons: Vec::new(),
+            tile_size: DEFAULT_TILE_SIZE,
+            max_depth: DEFAULT_MAX_DEPTH,
+            plan: None,
+        }
+    }
+
+    /// Adds a polygon to cover. Tiles cover the union of every polygon.
+    pub fn polygon(mut self, points: Vec<Coordinate>) -> Self {
+        if !points.is_empty() {
+            self.polygons.push(points);
+        }
+        self
+    }
+
+    /// Adds a neighbourhood boundary to cover. Points whose coordinates do
+    /// not parse are skipped.
+    pub fn boundary(self, boundary: &[LatLng]) -> Self {
+        let points = boundary
+            .iter()
+            .filter_map(|point| {
+                Some(Coordinate {
+                    lat: point.latitude.parse().ok()?,
+                    lng: point.longitude.parse().ok()?,
+                })
+            })
+            .collect();
+        self.polygon(points)
+    }
+
+    /// Sets the side of the initial grid tiles in degrees. Defaults to
+    /// 0.05° (roughly 5.5 km north to south); sizes that are not positive
+    /// and finite are ignored.
+    ///
+    /// The grid has at most 256 tiles along each side, so a size too small
+    /// for the area is enlarged to fit.
+    pub fn tile_size(mut self, degrees: f64) -> Self {
+        if degrees.is_finite() && degrees > 0.0 {
+            self.tile_size = degrees;
+        }
+        self
+    }
+
+    /// Sets how many times a tile may be split before a harvest gives up.
+    /// Defaults to 6.
+    pub fn max_depth(mut self, depth: u32) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Uses tiles from an earlier [`Harvest::plan`] instead of a fresh grid.
+    pub fn plan(mut self, tiles: Vec<Tile>) -> Self {
+        self.plan = Some(tiles);
+        self
+    }
+
+    /// Returns the tiles a harvest starts from: the plan if one was set,
+    /// otherwise the grid cells that overlap any polygon.
+    pub fn tiles(&self) -> Vec<Tile> {
+        if let Some(plan) = &self.plan {
+            return plan.clone();
+        }
+        let Some(bounds) = self.bounds() else {
+            return Vec::new();
+        };
+        let size = self
+            .tile_size
+            .max((bounds.north - bounds.south) / MAX_GRID_SIDE)
+            .max((bounds.east - bounds.west) / MAX_GRID_SIDE);
+        let rows = ((bounds.north - bounds.south) / size).ceil().max(1.0) as usize;
+        let columns = ((bounds.east - bounds.west) / size).ceil().max(1.0) as usize;
+        let mut tiles = Vec::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                let south = bounds.south + row as f64 * size;
+                let west = bounds.west + column as f64 * size;
+                let tile = Tile {
+                    bounds: BoundingBox {
+                        south,
+                        west,
+                        north: (south + size).min(bounds.north),
+                        east: (west + size).min(bounds.east),
+                    },
+                    depth: 0,
+                };
+                if self.overlaps(&tile.bounds) {
+                    tiles.push(tile);
+                }
+            }
+        }
+        tiles
+    }
+
+    /// Queries every tile for street-level crimes and collects the results.
+    ///
+    /// A tile that returns `503` (the API's response above
+    /// [`RESULT_CAP`]) or exactly the cap is split into quarters, and
+    /// quarters that miss every polygon are dropped. Crimes returned by
+    /// more than one tile are kept once, matched by `id`. A tile that fails
+    /// for another reason, or still hits the cap at the maximum depth, is
+    /// recorded in [`Harvest::failed`] and the remaining tiles are still
+    /// queried.
+    pub async fn harvest_crimes(
+        &self,
+        client: &Client,
+        category: &str,
+        date: Option<&str>,
+    ) -> Harvest {
+        let mut queue = self.tiles();
+        queue.reverse();
+        let mut seen = HashSet::new();
+        let mut harvest = Harvest {
+            crimes: Vec::new(),
+            tiles: Vec::new(),
+            failed: Vec::new(),
+            duplicates: 0,
+        };
+
+        while let Some(tile) = queue.pop() {
+            let result = client
+                .street_level_crimes(category, &tile.area(), date)
+                .await;
+            let over_cap = match &result {
+                Ok(crimes) => crimes.len() >= RESULT_CAP,
+                Err(Error::Api { status: 503, .. }) => true,
+                Err(_) => false,
+            };
+            if over_cap && tile.depth < self.max_depth {
+                // Reversed so the quarters are queried in order.
+                for quarter in tile.split().into_iter().rev() {
+                    if self.overlaps(&quarter.bounds) {
+                        queue.push(quarter);
+                    }
+                }
+                continue;
+            }
+
+            let crimes = match result {
+                Ok(crimes) => crimes,
+                Err(error) => {
+                    harvest.failed.push(TileFailure { tile, error });
+                    continue;
+                }
+            };
+            harvest.tiles.push(TileResult {
+                tile,
+                count: crimes.len(),
+            });
+            for crime in crimes {
+                if seen.insert(crime.id) {
+                    harvest.crimes.push(crime);
+                } else {
+                    harvest.duplicates += 1;
+                }
+            }
+        }
+        harvest
+    }
+
+    fn bounds(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.polygons.iter().flatten())
+    }
+
+    /// Returns `true` if the box overlaps any polygon. With no polygons (a
+    /// tiler built from a plan alone), everything overlaps.
+    fn overlaps(&self, bounds: &BoundingBox) -> bool {
+        self.polygons.is_empty()
+            || self
+                .polygons
+                .iter()
+                .any(|polygon| box_overlaps_polygon(bounds, polygon))
+    }
+}
+
+impl Default for Tiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `true` if the box and polygon share some area. Boxes that only
+/// touch the polygon along an edge or at a corner don't count, so grid
+/// cells beside a boundary aren't queried for nothing.
+fn box_overlaps_polygon(bounds: &BoundingBox, polygon: &[Coordinate]) -> bool {
+    // Shrinking the box slightly turns touching into missing.
+    let bounds = &BoundingBox {
+        south: bounds.south + TOUCH_TOLERANCE,
+        west: bounds.west + TOUCH_TOLERANCE,
+        north: bounds.north - TOUCH_TOLERANCE,
+        east: bounds.east - TOUCH_TOLERANCE,
+    };
+    if polygon
+        .iter()
+        .any(|point| bounds.contains(point.lat, point.lng))
+    {
+        return true;
+    }
+    let corners = [
+        (bounds.south, bounds.west),
+        (bounds.north, bounds.west),
+        (bounds.north, bounds.east),
+        (bounds.south, bounds.east),
+    ];
+    if corners
+        .iter()
+        .any(|&(lat, lng)| contains_point(polygon, lat, lng))
+    {
+        return true;
+    }
+    // Neither contains a vertex of the other, so they overlap only if
+    // their edges cross.
+    let box_edges = (0..4).map(|i| (corners[i], corners[(i + 1) % 4]));
+    let polygon_edges: Vec<_> = edges(polygon).collect();
+    box_edges.into_iter().any(|(a, b)| {
+        polygon_edges
+            .iter()
+            .any(|&(c, d)| segments_cross(a, b, c, d))
+    })
+}
+
+/// Edges of a polygon as `(lat, lng)` pairs, closing the ring.
+fn edges(polygon: &[Coordinate]) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    let n = polygon.len();
+    (0..n).map(move |i| {
+        let (a, b) = (&polygon[i], &polygon[(i + 1) % n]);
+        ((a.lat, a.lng), (b.lat, b.lng))
+    })
+}
+
+/// Ray-casting point-in-polygon test.
+fn contains_point(polygon: &[Coordinate], lat: f64, lng: f64) -> bool {
+    let mut inside = false;
+    for ((lat_a, lng_a), (lat_b, lng_b)) in edges(polygon) {
+        if (lat_a > lat) != (lat_b > lat)
+            && lng < (lng_b - lng_a) * (lat - lat_a) / (lat_b - lat_a) + lng_a
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn segments_cross(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let orientation = |p: (f64, f64), q: (f64, f64), r: (f64, f64)| {
+        ((q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0)).signum()
+    };
+    orientation(a, b, c) * orientation(a, b, d) < 0.0
+        && orientation(c, d, a) * orientation(c, d, b) < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    use super::*;
+
+    fn point(lat: f64, lng: f64) -> Coordinate {
+        Coordinate { lat, lng }
+    }
+
+    /// A right triangle filling the lower-left half of a 1° square.
+    fn triangle() -> Vec<Coordinate> {
+        vec![point(52.0, -1.0), point(53.0, -1.0), point(52.0, 0.0)]
+    }
+
+    #[test]
+    fn test_tiles_skip_cells_outside_polygon() {
+        let tiler = Tiler::new().polygon(triangle()).tile_size(0.25);
+        let tiles = tiler.tiles();
+        // 16 cells; the 6 entirely above the diagonal are dropped.
+        assert_eq!(tiles.len(), 10);
+        assert!(tiles.iter().all(|tile| tile.depth == 0));
+        let corner = BoundingBox {
+            south: 52.75,
+            west: -0.25,
+            north: 53.0,
+            east: 0.0,
+        };
+        assert!(!tiles.iter().any(|tile| tile.bounds == corner));
+    }
+
+    #[test]
+    fn test_tile_size_is_bounded() {
+        // Invalid sizes are ignored.
+        for size in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let tiler = Tiler::new().polygon(triangle()).tile_size(size);
+            assert_eq!(tiler.tile_size, DEFAULT_TILE_SIZE);
+        }
+        // A tiny size is enlarged so the grid stays at most 256 tiles a side.
+        let tiles = Tiler::new().polygon(triangle()).tile_size(1e-12).tiles();
+        assert!(!tiles.is_empty());
+        assert!(tiles.len() <= 256 * 256);
+    }
+
+    #[test]
+    fn test_overlap_by_crossing_edges_only() {
+        // A thin diamond whose vertices all lie outside the box, but whose
+        // edges cross it.
+        let diamond = vec![
+            point(51.0, 0.5),
+            point(52.5, 0.45),
+            point(54.0, 0.5),
+            point(52.5, 0.55),
+        ];
+        let bounds = BoundingBox {
+            south: 52.0,
+            west: 0.0,
+            north: 53.0,
+            east: 1.0,
+        };
+        assert!(box_overlaps_polygon(&bounds, &diamond));
+        let away = BoundingBox {
+            south: 52.0,
+            west: 2.0,
+            north: 53.0,
+            east: 3.0,
+        };
+        assert!(!box_overlaps_polygon(&away, &diamond));
+    }
+
+    #[test]
+    fn test_boundary_and_split() {
+        let boundary: Vec<LatLng> = serde_json::from_value(json!([
+            { "latitude": "52.0", "longitude": "-1.0" },
+            { "latitude": "53.0", "longitude": "-1.0" },
+            { "latitude": "bad", "longitude": "0.0" },
+            { "latitude": "52.0", "longitude": "0.0" }
+        ]))
+        .unwrap();
+        let tiler = Tiler::new().boundary(&boundary).tile_size(1.0);
+        let [tile] = tiler.tiles()[..] else {
+            panic!("expected one tile");
+        };
+        let quarters = tile.split();
+        assert_eq!(quarters[3].bounds.south, 52.5);
+        assert_eq!(quarters[3].bounds.west, -0.5);
+        assert_eq!(quarters[3].depth, 1);
+        let Area::Custom(corners) = quarters[0].area() else {
+            panic!("expected a custom area");
+        };
+        assert_eq!(corners.len(), 4);
+    }
+
+    fn crime(id: u64, lat: f64, lng: f64) -> serde_json::Value {
+        json!({
+            "category": "burglary",
+            "persistent_id": "",
+            "location_subtype": "",
+            "id": id,
+            "location": {
+                "latitude": lat.to_string(),
+                "street": { "id": id, "name": "On or near High Street" },
+                "longitude": lng.to_string()
+            },
+            "context": "",
+            "month": "2024-01",
+            "location_type": "Force",
+            "outcome_status": null
+        })
+    }
+
+    #[tokio::test]
+    async fn test_harvest_splits_over_cap_tiles_and_dedupes() {
+        // Crime 2 sits on the edge between the western and eastern quarters.
+        let crimes = [
+            crime(1, 52.2, -0.8),
+            crime(2, 52.2, -0.5),
+            crime(3, 52.7, -0.8),
+        ];
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crimes-street/all-crime"))
+            .respond_with(move |request: &Request| {
+                let poly = request
+                    .url
+                    .query_pairs()
+                    .find(|(key, _)| key == "poly")
+                    .unwrap()
+                    .1
+                    .into_owned();
+                let corners: Vec<(f64, f64)> = poly
+                    .split(':')
+                    .map(|pair| {
+                        let (lat, lng) = pair.split_once(',').unwrap();
+                        (lat.parse().unwrap(), lng.parse().unwrap())
+                    })
+                    .collect();
+                let bounds = BoundingBox {
+                    south: corners[0].0,
+                    west: corners[0].1,
+                    north: corners[2].0,
+                    east: corners[2].1,
+                };
+                // Pretend the whole square is over the cap.
+                if bounds.north - bounds.south > 0.9 {
+                    return ResponseTemplate::new(503);
+                }
+                let inside: Vec<_> = crimes
+                    .iter()
+                    .filter(|crime| {
+                        let location = &crime["location"];
+                        bounds.contains(
+                            location["latitude"].as_str().unwrap().parse().unwrap(),
+                            location["longitude"].as_str().unwrap().parse().unwrap(),
+                        )
+                    })
+                    .cloned()
+                    .collect();
+                ResponseTemplate::new(200).set_body_json(inside)
+            })
+            .mount(&server)
+            .await;
+
+        let client = Client::new().with_base_url(server.uri());
+        let tiler = Tiler::new().polygon(triangle()).tile_size(1.0);
+        let harvest = tiler
+            .harvest_crimes(&client, "all-crime", Some("2024-01"))
+            .await;
+        assert!(harvest.is_complete());
+
+        // The north-east quarter misses the triangle, so only three are queried.
+        assert_eq!(harvest.tiles.len(), 3);
+        assert!(harvest.tiles.iter().all(|result| result.tile.depth == 1));
+        let ids: Vec<u64> = harvest.crimes.iter().map(|crime| crime.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(harvest.duplicates, 1);
+
+        // Reusing the plan skips the failed top-level query.
+        let before = server.received_requests().await.unwrap().len();
+        let again = tiler
+            .clone()
+            .plan(harvest.plan())
+            .harvest_crimes(&client, "all-crime", Some("2024-01"))
+            .await;
+        assert_eq!(again.crimes, harvest.crimes);
+        assert_eq!(server.received_requests().await.unwrap().len() - before, 3);
+
+        // A tile still over the cap at the maximum depth is recorded as
+        // failed, and the others are still queried.
+        let tiler = Tiler::new()
+            .polygon(triangle())
+            .tile_size(0.5)
+            .plan(vec![
+                Tile {
+                    bounds: BoundingBox {
+                        south: 52.0,
+                        west: -1.0,
+                        north: 53.0,
+                        east: 0.0,
+                    },
+                    depth: 0,
+                },
+                harvest.tiles[0].tile,
+            ])
+            .max_depth(0);
+        let partial = tiler.harvest_crimes(&client, "all-crime", None).await;
+        assert!(!partial.is_complete());
+        assert_eq!(partial.failed.len(), 1);
+        assert!(matches!(
+            partial.failed[0].error,
+            Error::Api { status: 503, .. }
+        ));
+        assert_eq!(partial.tiles.len(), 1);
+        assert_eq!(partial.crimes.len(), 2);
+        assert_eq!(partial.plan().len(), 2);
+    }
+}
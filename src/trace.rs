@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use tracing::Span;
+use tracing::field::Empty;
+
+use crate::error::Error;
+use crate::models::Unrecognised;
+use crate::request::{Request, RequestArea, Response, ResultCount};
+
+const REDACTED: &str = "[redacted]";
+
+/// Creates the span that wraps a single endpoint call.
+///
+/// Fields that are only known once the call completes are declared empty and
+/// filled in by [`record_result`].
+pub(crate) fn request_span(request: &Request, redact: bool) -> Span {
+    let span = tracing::info_span!(
+        "uk_police_api.request",
+        endpoint = request.endpoint,
+        url = Empty,
+        force = Empty,
+        neighbourhood = Empty,
+        category = Empty,
+        month = Empty,
+        area.kind = Empty,
+        area.vertices = Empty,
+        location = Empty,
+        persistent_id = Empty,
+        http.status = Empty,
+        latency_ms = Empty,
+        coalesced = Empty,
+        cache_hit = Empty,
+        retries = Empty,
+        rate_limit_wait_ms = Empty,
+        result_count = Empty,
+    );
+    if span.is_disabled() {
+        return span;
+    }
+
+    let sensitive = |value: &dyn std::fmt::Display| {
+        if redact {
+            REDACTED.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    span.record("url", sensitive(&request.url));
+    if let Some(force) = &request.force {
+        span.record("force", force.as_str());
+    }
+    if let Some(neighbourhood) = &request.neighbourhood {
+        span.record("neighbourhood", neighbourhood.as_str());
+    }
+    if let Some(category) = &request.category {
+        span.record("category", category.as_str());
+    }
+    if let Some(month) = &request.month {
+        span.record("month", month.as_str());
+    }
+    match &request.area {
+        Some(RequestArea::Point(coord)) => {
+            span.record("area.kind", "point");
+            span.record(
+                "location",
+                sensitive(&format_args!("{},{}", coord.lat, coord.lng)),
+            );
+        }
+        Some(RequestArea::Polygon { vertices }) => {
+            span.record("area.kind", "polygon");
+            span.record("area.vertices", vertices);
+        }
+        Some(RequestArea::LocationId(id)) => {
+            span.record("area.kind", "location_id");
+            span.record("location", sensitive(id));
+        }
+        None => {}
+    }
+    if let Some(persistent_id) = &request.persistent_id {
+        span.record("persistent_id", sensitive(persistent_id));
+    }
+    span
+}
+
+/// Records whether the current request joined another caller's in-flight request.
+pub(crate) fn record_coalesced(coalesced: bool) {
+    Span::current().record("coalesced", coalesced);
+}
+
+/// Records the number of retries made so far for the current request.
+pub(crate) fn record_retry(attempt: u32) {
+    Span::current().record("retries", attempt);
+    tracing::debug!(attempt, "retrying request");
+}
+
+/// Records whether the current request was served from the response cache.
+pub(crate) fn record_cache(hit: bool) {
+    Span::current().record("cache_hit", hit);
+}
+
+/// Records how long the current request waited for the rate limiter.
+pub(crate) fn record_rate_limit_wait(wait: Duration) {
+    Span::current().record("rate_limit_wait_ms", wait.as_millis() as u64);
+}
+
+/// Records the outcome of a request on its span and emits a completion event.
+pub(crate) fn record_result<T: ResultCount + Unrecognised>(
+    span: &Span,
+    result: &Result<(Response, T), Error>,
+    latency: Duration,
+) {
+    let latency_ms = latency.as_millis() as u64;
+    span.record("latency_ms", latency_ms);
+    let _enter = span.enter();
+    match result {
+        Ok((response, value)) => {
+            let status = response.status;
+            span.record("http.status", status);
+            span.record("result_count", value.result_count());
+            tracing::debug!(status, latency_ms, "request completed");
+            for unrecognised in value.unrecognised_values() {
+                tracing::warn!(
+                    field = unrecognised.field,
+                    value = %unrecognised.value,
+                    count = unrecognised.count,
+                    "unrecognised value in response"
+                );
+            }
+        }
+        Err(err) => {
+            if let Error::Api { status, .. } = err {
+                span.record("http.status", status);
+            }
+            tracing::warn!(error = %err, latency_ms, "request failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::fmt::MakeWriter;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::Client;
+    use crate::models::{Area, Coordinate};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn subscriber(buffer: &Buffer) -> impl tracing::Subscriber {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(buffer.clone())
+            .finish()
+    }
+
+    #[tokio::test]
+    async fn test_span_records_parameters_and_result() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crimes-street/burglary"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let buffer = Buffer::default();
+        let _guard = tracing::subscriber::set_default(subscriber(&buffer));
+
+        let client = Client::new().with_base_url(server.uri());
+        let area = Area::Custom(vec![
+            Coordinate {
+                lat: 52.2,
+                lng: 0.5,
+            },
+            Coordinate {
+                lat: 52.7,
+                lng: 0.2,
+            },
+            Coordinate {
+                lat: 52.1,
+                lng: 0.4,
+            },
+        ]);
+        client
+            .street_level_crimes("burglary", &area, Some("2024-01"))
+            .await
+            .unwrap();
+
+        let output = buffer.contents();
+        assert!(output.contains("endpoint=\"street_level_crimes\""));
+        assert!(output.contains("category=\"burglary\""));
+        assert!(output.contains("month=\"2024-01\""));
+        assert!(output.contains("area.kind=\"polygon\""));
+        assert!(output.contains("area.vertices=3"));
+        assert!(output.contains("http.status=200"));
+        assert!(output.contains("result_count=0"));
+        assert!(output.contains("request completed"));
+    }
+
+    #[tokio::test]
+    async fn test_redaction_hides_sensitive_fields() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/locate-neighbourhood"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+
+        let buffer = Buffer::default();
+        let _guard = tracing::subscriber::set_default(subscriber(&buffer));
+
+        let client = Client::new()
+            .with_base_url(server.uri())
+            .with_tracing_redaction(true);
+        client
+            .locate_neighbourhood(51.5007, -0.1246)
+            .await
+            .unwrap_err();
+
+        let output = buffer.contents();
+        assert!(output.contains("area.kind=\"point\""));
+        assert!(output.contains("location=\"[redacted]\""));
+        assert!(output.contains("url=\"[redacted]\""));
+        assert!(output.contains("http.status=404"));
+        assert!(output.contains("request failed"));
+        assert!(!output.contains("51.5007"));
+    }
+}
@@ -0,0 +1,499 @@
+//! Polling for new data releases.
+//!
+//! A [`Watcher`] polls the API's `crime-last-updated` date, month
+//! availability and crime categories, and yields a [`ReleaseEvent`] for each
+//! change it sees between polls.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use futures_util::stream::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::models::ForceId;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_JITTER: f64 = 0.1;
+const DEFAULT_BASE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// What the API reported as published at one poll.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseSnapshot {
+    /// Date from `crime-last-updated`.
+    pub crime_last_updated: String,
+    /// Available months, with the forces that published stop and search
+    /// data for each.
+    pub months: BTreeMap<String, BTreeSet<ForceId>>,
+    /// Crime category names, keyed by category slug.
+    pub categories: BTreeMap<String, String>,
+}
+
+impl ReleaseSnapshot {
+    /// Fetches the current snapshot.
+    pub async fn fetch(client: &Client) -> Result<Self, Error> {
+        let crime_last_updated = client.crime_last_updated().await?.date;
+        let months = client
+            .crime_availability()
+            .await?
+            .into_iter()
+            .map(|month| (month.date, month.stop_and_search.into_iter().collect()))
+            .collect();
+        let categories = client
+            .crime_categories(None)
+            .await?
+            .into_iter()
+            .map(|category| (category.url, category.name))
+            .collect();
+        Ok(Self {
+            crime_last_updated,
+            months,
+            categories,
+        })
+    }
+
+    /// Returns the events that turn `self` into `current`.
+    pub fn changes(&self, current: &Self) -> Vec<ReleaseEvent> {
+        let mut events = Vec::new();
+        if self.crime_last_updated != current.crime_last_updated {
+            events.push(ReleaseEvent::DataUpdated {
+                previous: self.crime_last_updated.clone(),
+                current: current.crime_last_updated.clone(),
+            });
+        }
+
+        for (month, forces) in &current.months {
+            let Some(previous) = self.months.get(month) else {
+                events.push(ReleaseEvent::MonthPublished {
+                    month: month.clone(),
+                    stop_and_search: forces.iter().cloned().collect(),
+                });
+                continue;
+            };
+            for force in forces.difference(previous) {
+                events.push(ReleaseEvent::StopAndSearchForceAdded {
+                    month: month.clone(),
+                    force: force.clone(),
+                });
+            }
+            for force in previous.difference(forces) {
+                events.push(ReleaseEvent::StopAndSearchForceRemoved {
+                    month: month.clone(),
+                    force: force.clone(),
+                });
+            }
+        }
+        for month in self.months.keys() {
+            if !current.months.contains_key(month) {
+                events.push(ReleaseEvent::MonthWithdrawn {
+                    month: month.clone(),
+                });
+            }
+        }
+
+        for (url, name) in &current.categories {
+            match self.categories.get(url) {
+                None => events.push(ReleaseEvent::CategoryAdded {
+                    url: url.clone(),
+                    name: name.clone(),
+                }),
+                Some(previous) if previous != name => events.push(ReleaseEvent::CategoryRenamed {
+                    url: url.clone(),
+                    previous: previous.clone(),
+                    name: name.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (url, name) in &self.categories {
+            if !current.categories.contains_key(url) {
+                events.push(ReleaseEvent::CategoryRemoved {
+                    url: url.clone(),
+                    name: name.clone(),
+                });
+            }
+        }
+        events
+    }
+}
+
+/// A change in published data, yielded by a [`Watch`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReleaseEvent {
+    /// `crime-last-updated` changed.
+    DataUpdated { previous: String, current: String },
+    /// A month became available.
+    MonthPublished {
+        month: String,
+        /// Forces that published stop and search data for the month.
+        stop_and_search: Vec<ForceId>,
+    },
+    /// A month is no longer available.
+    MonthWithdrawn { month: String },
+    /// A force published stop and search data for an existing month.
+    StopAndSearchForceAdded { month: String, force: ForceId },
+    /// A force's stop and search data for a month is no longer available.
+    StopAndSearchForceRemoved { month: String, force: ForceId },
+    /// A crime category was added.
+    CategoryAdded { url: String, name: String },
+    /// A crime category was removed.
+    CategoryRemoved { url: String, name: String },
+    /// A crime category's name changed.
+    CategoryRenamed {
+        url: String,
+        previous: String,
+        name: String,
+    },
+}
+
+/// Polls for newly published data and yields [`ReleaseEvent`]s.
+///
+/// The first poll happens immediately and records a baseline without
+/// yielding events, unless a snapshot is passed to [`Watcher::resume`].
+/// Failed polls yield the error and are retried with exponential backoff.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use futures_util::StreamExt;
+/// use uk_police_api::Client;
+/// use uk_police_api::watch::{ReleaseEvent, Watcher};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut watch = Watcher::new(Client::new())
+///     .interval(Duration::from_secs(15 * 60))
+///     .watch();
+/// while let Some(event) = watch.next().await {
+///     match event {
+///         Ok(ReleaseEvent::MonthPublished { month, .. }) => println!("{month} published"),
+///         Ok(other) => println!("{other:?}"),
+///         Err(err) => eprintln!("poll failed: {err}"),
+///     }
+/// }
+/// # }
+/// ```
+pub struct Watcher {
+    client: Client,
+    interval: Duration,
+    jitter: f64,
+    base_backoff: Duration,
+    max_backoff: Duration,
+    snapshot: Option<ReleaseSnapshot>,
+}
+
+impl Watcher {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            interval: DEFAULT_INTERVAL,
+            jitter: DEFAULT_JITTER,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            max_backoff: DEFAULT_INTERVAL,
+            snapshot: None,
+        }
+    }
+
+    /// Sets the time between polls. Defaults to one hour.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the fraction by which each delay is randomly lengthened or
+    /// shortened, so many watchers do not poll in step. Defaults to 0.1
+    /// (±10%); clamped to `0.0..=1.0`.
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the delay after the first failed poll, which doubles with each
+    /// consecutive failure up to `max`. Defaults to 30s, up to one hour.
+    pub fn backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_backoff = base;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Compares the first poll against a snapshot from an earlier
+    /// [`Watch::snapshot`], so changes made while not watching are reported.
+    pub fn resume(mut self, snapshot: ReleaseSnapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Starts watching.
+    pub fn watch(self) -> Watch {
+        let poll = poll(self.client.clone(), Duration::ZERO);
+        Watch {
+            watcher: self,
+            pending: VecDeque::new(),
+            failures: 0,
+            poll,
+        }
+    }
+}
+
+/// A running watcher. Yields events as they are detected; never ends.
+pub struct Watch {
+    watcher: Watcher,
+    pending: VecDeque<ReleaseEvent>,
+    failures: u32,
+    poll: BoxFuture<'static, Result<ReleaseSnapshot, Error>>,
+}
+
+impl Watch {
+    /// Returns the snapshot from the last successful poll.
+    pub fn snapshot(&self) -> Option<&ReleaseSnapshot> {
+        self.watcher.snapshot.as_ref()
+    }
+
+    fn next_delay(&self) -> Duration {
+        let delay = if self.failures == 0 {
+            self.watcher.interval
+        } else {
+            self.watcher
+                .base_backoff
+                .saturating_mul(2u32.saturating_pow(self.failures - 1))
+                .min(self.watcher.max_backoff)
+        };
+        let jitter = self.watcher.jitter;
+        if jitter == 0.0 {
+            return delay;
+        }
+        let factor = 1.0 + jitter * (fastrand::f64() * 2.0 - 1.0);
+        Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(Duration::MAX)
+    }
+}
+
+impl Stream for Watch {
+    type Item = Result<ReleaseEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+            let result = ready!(this.poll.as_mut().poll(cx));
+            match result {
+                Ok(snapshot) => {
+                    this.failures = 0;
+                    if let Some(previous) = &this.watcher.snapshot {
+                        this.pending.extend(previous.changes(&snapshot));
+                    }
+                    this.watcher.snapshot = Some(snapshot);
+                    this.poll = poll(this.watcher.client.clone(), this.next_delay());
+                }
+                Err(err) => {
+                    this.failures = this.failures.saturating_add(1);
+                    this.poll = poll(this.watcher.client.clone(), this.next_delay());
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+    }
+}
+
+fn poll(client: Client, delay: Duration) -> BoxFuture<'static, Result<ReleaseSnapshot, Error>> {
+    Box::pin(async move {
+        tokio::time::sleep(delay).await;
+        ReleaseSnapshot::fetch(&client).await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    /// Mounts a release. Later releases use a higher `priority` so they
+    /// replace earlier ones without a gap a poll could land in.
+    async fn mock_release(
+        server: &MockServer,
+        priority: u8,
+        last_updated: &str,
+        months: serde_json::Value,
+        categories: serde_json::Value,
+    ) {
+        for (route, body) in [
+            ("/crime-last-updated", json!({ "date": last_updated })),
+            ("/crimes-street-dates", months),
+            ("/crime-categories", categories),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .with_priority(priority)
+                .mount(server)
+                .await;
+        }
+    }
+
+    fn force(id: &str) -> ForceId {
+        id.parse().unwrap()
+    }
+
+    #[test]
+    fn test_changes() {
+        let previous = ReleaseSnapshot {
+            crime_last_updated: "2024-01-01".to_string(),
+            months: BTreeMap::from([
+                ("2023-12".to_string(), BTreeSet::from([force("kent")])),
+                ("2024-01".to_string(), BTreeSet::from([force("kent")])),
+            ]),
+            categories: BTreeMap::from([
+                ("burglary".to_string(), "Burglary".to_string()),
+                ("drugs".to_string(), "Drugs".to_string()),
+                ("other-crime".to_string(), "Other crime".to_string()),
+            ]),
+        };
+        let current = ReleaseSnapshot {
+            crime_last_updated: "2024-02-01".to_string(),
+            months: BTreeMap::from([
+                ("2024-01".to_string(), BTreeSet::from([force("essex")])),
+                ("2024-02".to_string(), BTreeSet::from([force("kent")])),
+            ]),
+            categories: BTreeMap::from([
+                ("burglary".to_string(), "Burglary".to_string()),
+                ("drugs".to_string(), "Drug offences".to_string()),
+                ("shoplifting".to_string(), "Shoplifting".to_string()),
+            ]),
+        };
+        assert_eq!(
+            previous.changes(&current),
+            [
+                ReleaseEvent::DataUpdated {
+                    previous: "2024-01-01".to_string(),
+                    current: "2024-02-01".to_string(),
+                },
+                ReleaseEvent::StopAndSearchForceAdded {
+                    month: "2024-01".to_string(),
+                    force: force("essex"),
+                },
+                ReleaseEvent::StopAndSearchForceRemoved {
+                    month: "2024-01".to_string(),
+                    force: force("kent"),
+                },
+                ReleaseEvent::MonthPublished {
+                    month: "2024-02".to_string(),
+                    stop_and_search: vec![force("kent")],
+                },
+                ReleaseEvent::MonthWithdrawn {
+                    month: "2023-12".to_string(),
+                },
+                ReleaseEvent::CategoryRenamed {
+                    url: "drugs".to_string(),
+                    previous: "Drugs".to_string(),
+                    name: "Drug offences".to_string(),
+                },
+                ReleaseEvent::CategoryAdded {
+                    url: "shoplifting".to_string(),
+                    name: "Shoplifting".to_string(),
+                },
+                ReleaseEvent::CategoryRemoved {
+                    url: "other-crime".to_string(),
+                    name: "Other crime".to_string(),
+                },
+            ]
+        );
+        assert!(current.changes(&current).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_yields_events_after_baseline() {
+        let server = MockServer::start().await;
+        let categories = json!([{ "url": "burglary", "name": "Burglary" }]);
+        mock_release(
+            &server,
+            2,
+            "2024-01-01",
+            json!([{ "date": "2024-01", "stop-and-search": ["kent"] }]),
+            categories.clone(),
+        )
+        .await;
+
+        let mut watch = Watcher::new(Client::new().with_base_url(server.uri()))
+            .interval(Duration::from_millis(20))
+            .jitter(0.0)
+            .watch();
+        // The baseline poll yields nothing, so publish once it has happened.
+        let publish = async {
+            while server.received_requests().await.unwrap().len() < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            mock_release(
+                &server,
+                1,
+                "2024-02-01",
+                json!([
+                    { "date": "2024-02", "stop-and-search": [] },
+                    { "date": "2024-01", "stop-and-search": ["kent"] }
+                ]),
+                categories,
+            )
+            .await;
+        };
+        let (first, ()) = tokio::join!(watch.next(), publish);
+        assert!(matches!(first, Some(Ok(ReleaseEvent::DataUpdated { .. }))));
+        assert_eq!(
+            watch.next().await.unwrap().unwrap(),
+            ReleaseEvent::MonthPublished {
+                month: "2024-02".to_string(),
+                stop_and_search: Vec::new(),
+            }
+        );
+        assert_eq!(watch.snapshot().unwrap().months.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_watch_backs_off_on_errors() {
+        let server = MockServer::start().await;
+        let mut watch = Watcher::new(Client::new().with_base_url(server.uri()))
+            .interval(Duration::from_secs(3600))
+            .backoff(Duration::from_millis(10), Duration::from_millis(15))
+            .jitter(0.0)
+            .resume(ReleaseSnapshot::default())
+            .watch();
+
+        for _ in 0..2 {
+            let err = watch.next().await.unwrap().unwrap_err();
+            assert!(matches!(err, Error::Api { status: 404, .. }));
+        }
+        assert_eq!(watch.failures, 2);
+        assert_eq!(watch.next_delay(), Duration::from_millis(15));
+
+        mock_release(&server, 1, "2024-01-01", json!([]), json!([])).await;
+        let event = watch.next().await.unwrap().unwrap();
+        assert_eq!(
+            event,
+            ReleaseEvent::DataUpdated {
+                previous: String::new(),
+                current: "2024-01-01".to_string(),
+            }
+        );
+        assert_eq!(watch.failures, 0);
+    }
+
+    #[test]
+    fn test_jitter_saturates_long_delays() {
+        let watch = Watcher::new(Client::new())
+            .interval(Duration::MAX)
+            .jitter(1.0)
+            .watch();
+        // Lengthening the delay would overflow, so it saturates instead.
+        assert!((0..100).any(|_| watch.next_delay() == Duration::MAX));
+    }
+}
@@ -1,9 +1,13 @@
-use uk_police_api::{Area, Client, Coordinate, Error};
+use uk_police_api::{Area, Client, Coordinate, Error, ForceId, NeighbourhoodKey};
 
 fn client() -> Client {
     Client::new()
 }
 
+fn leicestershire() -> ForceId {
+    "leicestershire".parse().unwrap()
+}
+
 // --- Forces ---
 
 #[tokio::test]
@@ -16,8 +20,8 @@ async fn forces_returns_non_empty_list() {
 #[tokio::test]
 #[ignore]
 async fn force_returns_details() {
-    let force = client().force("leicestershire").await.unwrap();
-    assert_eq!(force.id, "leicestershire");
+    let force = client().force(&leicestershire()).await.unwrap();
+    assert_eq!(force.id, leicestershire());
     assert!(!force.name.is_empty());
 }
 
@@ -25,7 +29,14 @@ async fn force_returns_details() {
 #[ignore]
 async fn senior_officers_returns_list() {
     // May be empty for some forces, but the call itself should succeed
-    let _ = client().senior_officers("leicestershire").await.unwrap();
+    let _ = client().senior_officers(&leicestershire()).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn force_catalogue_matches_live_forces() {
+    let drift = client().force_catalogue_drift().await.unwrap();
+    assert!(drift.is_empty(), "force catalogue drift: {drift:?}");
 }
 
 // --- Crime ---
@@ -44,6 +55,14 @@ async fn crime_last_updated_returns_date() {
     assert!(!updated.date.is_empty());
 }
 
+#[tokio::test]
+#[ignore]
+async fn crime_availability_lists_months() {
+    let availability = client().crime_availability().await.unwrap();
+    assert!(!availability.is_empty());
+    assert_eq!(availability[0].date.len(), 7);
+}
+
 #[tokio::test]
 #[ignore]
 async fn street_level_crimes_near_known_point() {
@@ -98,7 +117,7 @@ async fn crimes_at_location_returns_results() {
 async fn crimes_no_location_returns_list() {
     // May be empty, but the call should succeed
     let _ = client()
-        .crimes_no_location("all-crime", "leicestershire", None)
+        .crimes_no_location("all-crime", &leicestershire(), None)
         .await
         .unwrap();
 }
@@ -117,12 +136,11 @@ async fn outcomes_for_crime_returns_result() {
         .unwrap();
     let persistent_id = crimes
         .iter()
-        .find(|c| !c.persistent_id.is_empty())
-        .map(|c| c.persistent_id.clone())
+        .find_map(|c| c.persistent_id.clone())
         .expect("expected at least one crime with a persistent_id");
 
     let outcomes = client().outcomes_for_crime(&persistent_id).await.unwrap();
-    assert_eq!(outcomes.crime.persistent_id, persistent_id);
+    assert_eq!(outcomes.crime.persistent_id, Some(persistent_id));
 }
 
 // --- Neighbourhoods ---
@@ -130,18 +148,18 @@ async fn outcomes_for_crime_returns_result() {
 #[tokio::test]
 #[ignore]
 async fn neighbourhoods_returns_non_empty_list() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     assert!(!neighbourhoods.is_empty());
 }
 
 #[tokio::test]
 #[ignore]
 async fn neighbourhood_returns_details() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     let first = &neighbourhoods[0];
 
     let detail = client()
-        .neighbourhood("leicestershire", &first.id)
+        .neighbourhood(&NeighbourhoodKey::new(leicestershire(), first.id.clone()))
         .await
         .unwrap();
     assert_eq!(detail.id, first.id);
@@ -150,11 +168,11 @@ async fn neighbourhood_returns_details() {
 #[tokio::test]
 #[ignore]
 async fn neighbourhood_boundary_returns_points() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     let first = &neighbourhoods[0];
 
     let boundary = client()
-        .neighbourhood_boundary("leicestershire", &first.id)
+        .neighbourhood_boundary(&NeighbourhoodKey::new(leicestershire(), first.id.clone()))
         .await
         .unwrap();
     assert!(!boundary.is_empty());
@@ -163,12 +181,12 @@ async fn neighbourhood_boundary_returns_points() {
 #[tokio::test]
 #[ignore]
 async fn neighbourhood_team_returns_list() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     let first = &neighbourhoods[0];
 
     // May be empty, but the call should succeed
     let _ = client()
-        .neighbourhood_team("leicestershire", &first.id)
+        .neighbourhood_team(&NeighbourhoodKey::new(leicestershire(), first.id.clone()))
         .await
         .unwrap();
 }
@@ -176,12 +194,12 @@ async fn neighbourhood_team_returns_list() {
 #[tokio::test]
 #[ignore]
 async fn neighbourhood_events_returns_list() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     let first = &neighbourhoods[0];
 
     // May be empty, but the call should succeed
     let _ = client()
-        .neighbourhood_events("leicestershire", &first.id)
+        .neighbourhood_events(&NeighbourhoodKey::new(leicestershire(), first.id.clone()))
         .await
         .unwrap();
 }
@@ -189,12 +207,12 @@ async fn neighbourhood_events_returns_list() {
 #[tokio::test]
 #[ignore]
 async fn neighbourhood_priorities_returns_list() {
-    let neighbourhoods = client().neighbourhoods("leicestershire").await.unwrap();
+    let neighbourhoods = client().neighbourhoods(&leicestershire()).await.unwrap();
     let first = &neighbourhoods[0];
 
     // May be empty, but the call should succeed
     let _ = client()
-        .neighbourhood_priorities("leicestershire", &first.id)
+        .neighbourhood_priorities(&NeighbourhoodKey::new(leicestershire(), first.id.clone()))
         .await
         .unwrap();
 }
@@ -206,7 +224,7 @@ async fn locate_neighbourhood_big_ben() {
         .locate_neighbourhood(51.5007, -0.1246)
         .await
         .unwrap();
-    assert_eq!(result.force, "metropolitan");
+    assert_eq!(result.force.as_str(), "metropolitan");
 }
 
 // --- Stop and Search ---
@@ -243,7 +261,7 @@ async fn stops_at_location_returns_results() {
 #[ignore]
 async fn stops_no_location_returns_list() {
     let _ = client()
-        .stops_no_location("leicestershire", None)
+        .stops_no_location(&leicestershire(), None)
         .await
         .unwrap();
 }
@@ -251,7 +269,7 @@ async fn stops_no_location_returns_list() {
 #[tokio::test]
 #[ignore]
 async fn stops_force_returns_list() {
-    let _ = client().stops_force("leicestershire", None).await.unwrap();
+    let _ = client().stops_force(&leicestershire(), None).await.unwrap();
 }
 
 // --- Error cases ---
@@ -259,7 +277,10 @@ async fn stops_force_returns_list() {
 #[tokio::test]
 #[ignore]
 async fn nonexistent_force_returns_api_error() {
-    let err = client().force("nonexistent-force-id").await.unwrap_err();
+    let err = client()
+        .force(&"nonexistent-force-id".parse().unwrap())
+        .await
+        .unwrap_err();
     match err {
         Error::Api { status, .. } => assert_eq!(status, 404),
         other => panic!("expected Error::Api with 404, got: {other}"),